@@ -0,0 +1,92 @@
+//! Rucksack priorities over a large generated inventory, with item sets as
+//! bitsets against the `HashSet<char>` they replaced.
+
+use std::collections::HashSet;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Groups of 3 sacks, each with one item in both compartments and one
+/// badge shared by the group, drawn by a linear congruential generator.
+fn inventory(groups: usize) -> String {
+    let mut seed: u64 = 0x2022_1203;
+    let mut rand = move |n: usize| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (seed >> 33) as usize % n
+    };
+    let mut out = String::new();
+    for _ in 0..groups {
+        let mut items = ITEMS.to_vec();
+        for i in (1..items.len()).rev() {
+            items.swap(i, rand(i + 1));
+        }
+        let (badge, rest) = items.split_first().unwrap();
+        for own in rest.chunks(rest.len() / 3) {
+            let (shared, own) = own.split_first().unwrap();
+            let (left, right) = own.split_at(own.len() / 2);
+            let mut c1 = vec![*badge, *shared];
+            let mut c2 = vec![*shared];
+            c1.extend((0..10).map(|_| left[rand(left.len())]));
+            c2.extend((0..11).map(|_| right[rand(right.len())]));
+            out.extend(c1.into_iter().chain(c2).map(char::from));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn priority(c: char) -> u64 {
+    match c {
+        'a'..='z' => c as u64 - 'a' as u64 + 1,
+        _ => c as u64 - 'A' as u64 + 27,
+    }
+}
+
+/// Both parts the way they were solved with hash sets.
+fn hash_sets(input: &str) -> (u64, u64) {
+    let sacks: Vec<(HashSet<char>, HashSet<char>)> = input
+        .lines()
+        .map(|line| {
+            let (c1, c2) = line.split_at(line.len() / 2);
+            (c1.chars().collect(), c2.chars().collect())
+        })
+        .collect();
+    let part1 = sacks
+        .iter()
+        .map(|(c1, c2)| priority(*c1.intersection(c2).next().unwrap()))
+        .sum();
+    let part2 = sacks
+        .chunks_exact(3)
+        .map(|group| {
+            let contents: Vec<HashSet<char>> = group
+                .iter()
+                .map(|(c1, c2)| c1.union(c2).copied().collect())
+                .collect();
+            let shared: HashSet<char> =
+                contents[0].intersection(&contents[1]).copied().collect();
+            priority(*shared.intersection(&contents[2]).next().unwrap())
+        })
+        .sum();
+    (part1, part2)
+}
+
+fn item_sets(input: &str) -> (u64, u64) {
+    let alphabet = aoc2022::day_03::Alphabet::default();
+    let data =
+        aoc2022::day_03::Data::read(input.as_bytes(), alphabet).unwrap();
+    (data.solve1().unwrap(), data.solve2().unwrap())
+}
+
+fn bench(c: &mut Criterion) {
+    let input = inventory(100_000);
+    assert_eq!(hash_sets(&input), item_sets(&input));
+    let mut group = c.benchmark_group("day_03");
+    group.sample_size(10);
+    group.bench_function("hash_sets", |b| b.iter(|| hash_sets(&input)));
+    group.bench_function("item_sets", |b| b.iter(|| item_sets(&input)));
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
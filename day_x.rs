@@ -5,6 +5,7 @@ use anyhow::{anyhow, Result};
 pub struct Data {}
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let file = std::fs::File::open(input).map_err(|e| {
             anyhow!("Failure to open input file {:?}: {:?}", input, e)
@@ -15,15 +16,17 @@ impl Data {
             .map(|(i, l)| (i + 1, l))
         {
             let line = line_result?;
-            eprintln!("{}: {:?}", ln, line);
+            tracing::debug!(ln, ?line);
         }
         Ok(Self {})
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<u64> {
         todo!();
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<u64> {
         todo!();
     }
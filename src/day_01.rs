@@ -1,47 +1,303 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::io::BufRead; // lines()
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    /// 1-based position in the input.
+    pub index: usize,
+    /// Calories of each food item carried.
+    pub items: Vec<u64>,
+}
+
+impl Elf {
+    pub fn total(&self) -> u64 {
+        self.items.iter().sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub elves: usize,
+    pub items: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+    pub min_items: usize,
+    pub max_items: usize,
+}
+
+/// How blank lines separating the elves are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Exactly one empty line between elves, none before the first or after
+    /// the last, and no whitespace around the numbers.
+    Strict,
+    /// Any run of blank lines separates elves, leading and trailing ones are
+    /// ignored, and whitespace around the numbers is trimmed.
+    Lenient,
+}
+
+/// Reads one elf at a time, so only a single elf's items are held in memory.
+pub struct Elves<R> {
+    lines: std::iter::Enumerate<std::io::Lines<R>>,
+    mode: Mode,
+    index: usize,
+    // Line number of the blank line which ended the previous elf.
+    separator: Option<usize>,
+}
+
+impl<R: BufRead> Elves<R> {
+    pub fn new(reader: R, mode: Mode) -> Self {
+        Self {
+            lines: reader.lines().enumerate(),
+            mode,
+            index: 0,
+            separator: None,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Elves<R> {
+    type Item = Result<Elf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut items = Vec::new();
+        for (i, line_result) in self.lines.by_ref() {
+            let ln = i + 1;
+            let line = match line_result {
+                Ok(line) => line,
+                Err(e) => {
+                    return Some(Err(anyhow!(
+                        "failure to read line {ln}: {e}"
+                    )))
+                }
+            };
+            let line = match self.mode {
+                Mode::Strict => line.as_str(),
+                Mode::Lenient => line.trim(),
+            };
+            match (line.is_empty(), items.is_empty(), self.mode) {
+                (true, true, Mode::Lenient) => continue,
+                (true, true, Mode::Strict) => {
+                    return Some(Err(anyhow!("empty group at line {ln}")))
+                }
+                (true, false, _) => {
+                    self.separator = Some(ln);
+                    break;
+                }
+                (false, _, _) => match line.parse::<u64>() {
+                    Ok(calories) => {
+                        self.separator = None;
+                        items.push(calories)
+                    }
+                    Err(e) => {
+                        return Some(Err(anyhow!(
+                            "invalid calories {line:?} on line {ln}: {e}"
+                        )))
+                    }
+                },
+            }
+        }
+        if !items.is_empty() {
+            self.index += 1;
+            return Some(Ok(Elf {
+                index: self.index,
+                items,
+            }));
+        }
+        match (self.separator.take(), self.mode) {
+            (Some(ln), Mode::Strict) => {
+                Some(Err(anyhow!("trailing blank line {ln}")))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Bounded min-heap of the N highest totals seen so far.
+pub struct TopN {
+    n: usize,
+    // Lowest total, and latest among equals, on top to be evicted first.
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopN {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    /// Streams elves from the reader, keeping only the top N totals.
+    pub fn from_reader<R: BufRead>(
+        reader: R,
+        mode: Mode,
+        n: usize,
+    ) -> Result<Self> {
+        let mut top = Self::new(n);
+        for elf_result in Elves::new(reader, mode) {
+            let elf = elf_result?;
+            top.push(elf.index, elf.total());
+        }
+        Ok(top)
+    }
+
+    pub fn push(&mut self, index: usize, total: u64) {
+        self.heap.push(Reverse((total, Reverse(index))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// (elf index, total) pairs, most first, and in order of appearance
+    /// among equals.
+    pub fn into_sorted_vec(self) -> Vec<(usize, u64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| (index, total))
+            .collect()
+    }
+}
 
 pub struct Data {
-    rev_sorted_totals: Vec<u64>,
+    elves: Vec<Elf>,
 }
 
 impl Data {
+    /// Loads in [`Mode::Strict`].
     pub fn load(input: &std::path::Path) -> Result<Self> {
+        Self::load_with_mode(input, Mode::Strict)
+    }
+
+    #[tracing::instrument]
+    pub fn load_with_mode(
+        input: &std::path::Path,
+        mode: Mode,
+    ) -> Result<Self> {
         let file = std::fs::File::open(input)?;
-        let mut elves: Vec<Vec<u64>> = Vec::new();
-        let mut elf: Vec<u64> = Vec::new();
-        for line_result in std::io::BufReader::new(file).lines() {
-            let line = line_result?;
-            if line.is_empty() {
-                elves.push(elf.clone());
-                elf.clear();
-            } else {
-                let calories: u64 = line.parse()?;
-                elf.push(calories);
-            }
-        }
-        elves.push(elf.clone());
-        let mut totals: Vec<u64> =
-            elves.iter().map(|elf| elf.iter().sum()).collect();
-        totals.sort();
-        totals.reverse();
-        Ok(Data {
-            rev_sorted_totals: totals,
-        })
+        let elves = Elves::new(std::io::BufReader::new(file), mode)
+            .collect::<Result<Vec<Elf>>>()?;
+        Ok(Data { elves })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<u64> {
-        match self.rev_sorted_totals[..] {
-            [] => Err(anyhow::anyhow!("zero elves in data")),
-            [highest, ..] => Ok(highest),
+        match self.top_n(1)[..] {
+            [] => Err(anyhow!("zero elves in data")),
+            [(_, highest, _)] => Ok(highest),
+            _ => unreachable!(),
         }
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<u64> {
-        match self.rev_sorted_totals[..] {
-            [t1, t2, t3, ..] => Ok(t1 + t2 + t3),
-            _ => Err(anyhow::anyhow!("less than 3 elves in data")),
+        match self.top_n(3)[..] {
+            [(_, t1, _), (_, t2, _), (_, t3, _)] => Ok(t1 + t2 + t3),
+            _ => Err(anyhow!("less than 3 elves in data")),
         }
     }
+
+    pub fn elves(&self) -> impl Iterator<Item = &Elf> {
+        self.elves.iter()
+    }
+
+    /// Elves carrying the most calories, as (elf index, total, items), most
+    /// first, and in order of appearance among equals.
+    pub fn top_n(&self, n: usize) -> Vec<(usize, u64, &[u64])> {
+        let mut top = TopN::new(n);
+        for elf in self.elves.iter() {
+            top.push(elf.index, elf.total());
+        }
+        top.into_sorted_vec()
+            .into_iter()
+            .map(|(index, total)| {
+                (index, total, &self.elves[index - 1].items[..])
+            })
+            .collect()
+    }
+
+    /// Summary of the calorie totals and item counts per elf, unless there
+    /// are no elves.
+    pub fn stats(&self) -> Option<Stats> {
+        let mut totals: Vec<u64> =
+            self.elves.iter().map(Elf::total).collect();
+        totals.sort();
+        let n = totals.len();
+        let median = match n {
+            0 => return None,
+            _ if n.is_multiple_of(2) => {
+                (totals[n / 2 - 1] + totals[n / 2]) as f64 / 2.0
+            }
+            _ => totals[n / 2] as f64,
+        };
+        let items = self.elves.iter().map(|elf| elf.items.len());
+        Some(Stats {
+            elves: n,
+            items: items.clone().sum(),
+            min: totals[0],
+            max: totals[n - 1],
+            mean: totals.iter().sum::<u64>() as f64 / n as f64,
+            median,
+            min_items: items.clone().min().unwrap_or(0),
+            max_items: items.max().unwrap_or(0),
+        })
+    }
+}
+
+#[test]
+fn elf_inventory() {
+    let data =
+        Data::load(std::path::Path::new("tests/input/day_01/example.txt"))
+            .unwrap();
+    assert_eq!(5, data.elves().count());
+    assert_eq!(
+        vec![
+            (4, 24000, &[7000, 8000, 9000][..]),
+            (3, 11000, &[5000, 6000])
+        ],
+        data.top_n(2)
+    );
+    let stats = data.stats().unwrap();
+    assert_eq!((5, 10), (stats.elves, stats.items));
+    assert_eq!((4000, 24000), (stats.min, stats.max));
+    assert_eq!((11000.0, 10000.0), (stats.mean, stats.median));
+    assert_eq!((1, 3), (stats.min_items, stats.max_items));
+}
+
+#[test]
+fn top_n_streaming() {
+    let log = "1\n2\n\n5\n\n3\n\n5\n\n4";
+    let top = |n| TopN::from_reader(log.as_bytes(), Mode::Strict, n).unwrap();
+    assert_eq!(vec![(2, 5), (4, 5), (5, 4)], top(3).into_sorted_vec());
+    assert_eq!(vec![(2, 5)], top(1).into_sorted_vec());
+    assert_eq!(5, top(9).into_sorted_vec().len());
+    assert_eq!(Vec::<(usize, u64)>::new(), top(0).into_sorted_vec());
+}
+
+#[test]
+fn grouping_modes() {
+    let totals = |log: &str, mode| -> Result<Vec<u64>> {
+        Elves::new(log.as_bytes(), mode)
+            .map(|elf| elf.map(|elf| elf.total()))
+            .collect()
+    };
+    let clean = "1\n2\n\n3\n";
+    let messy = "\n 1\r\n2\r\n\r\n \n\t\n3 \n\n";
+    assert_eq!(vec![3, 3], totals(clean, Mode::Strict).unwrap());
+    assert_eq!(vec![3, 3], totals(clean, Mode::Lenient).unwrap());
+    assert_eq!(vec![3, 3], totals(messy, Mode::Lenient).unwrap());
+    assert_eq!(Vec::<u64>::new(), totals("", Mode::Strict).unwrap());
+    let err = |log| totals(log, Mode::Strict).unwrap_err().to_string();
+    assert_eq!("empty group at line 3", err("1\n\n\n2"));
+    assert_eq!("empty group at line 1", err("\n1"));
+    assert_eq!("trailing blank line 2", err("1\n\n"));
+    assert!(err("1\n \n2").starts_with("invalid calories \" \" on line 2"));
+    // Line endings are up to BufRead::lines, which accepts CRLF either way.
+    assert_eq!(vec![1, 2], totals("1\r\n\r\n2\r\n", Mode::Strict).unwrap());
 }
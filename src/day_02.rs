@@ -2,7 +2,8 @@ use std::io::BufRead; // lines()
 
 use anyhow::{anyhow, Result};
 
-enum Outcome {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
     Win,
     Lose,
     Draw,
@@ -17,146 +18,732 @@ impl Outcome {
             _ => Err(anyhow!("invalid strategy: {s:?}")),
         }
     }
-}
 
-#[derive(Clone)]
-enum Choice {
-    Rock,
-    Paper,
-    Scissors,
+    /// The same outcome, from the other player's point of view.
+    pub fn reverse(&self) -> Self {
+        match self {
+            Self::Win => Self::Lose,
+            Self::Lose => Self::Win,
+            Self::Draw => Self::Draw,
+        }
+    }
 }
 
+/// Position of a choice in the cycle of its [`Rules`], which hand them out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Choice(usize);
+
 impl Choice {
-    fn parse(s: &str) -> Result<Self> {
-        match s {
-            "A" => Ok(Self::Rock),
-            "B" => Ok(Self::Paper),
-            "C" => Ok(Self::Scissors),
-            _ => Err(anyhow!("invalid choice: {s:?}")),
-        }
+    // Every game has at least these three.
+    pub const ROCK: Self = Self(0);
+    pub const PAPER: Self = Self(1);
+    pub const SCISSORS: Self = Self(2);
+
+    pub fn index(&self) -> usize {
+        self.0
     }
+}
 
-    fn points(&self) -> u64 {
-        match self {
-            Choice::Rock => 1,
-            Choice::Paper => 2,
-            Choice::Scissors => 3,
+/// A game of an odd number of choices arranged in a cycle, where each choice
+/// beats the ones an odd number of steps behind it and loses to the rest.
+///
+/// The opponent's choices are lettered from `A` onwards and ours are
+/// lettered so that the last one is `Z`, which for three choices gives the
+/// classic `A`/`B`/`C` versus `X`/`Y`/`Z`.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    names: Vec<String>,
+    choice_points: Vec<u64>,
+    lose_points: u64,
+    draw_points: u64,
+    win_points: u64,
+    // Outcome for the 2nd player, indexed by the 1st player's choice, then
+    // the 2nd player's.
+    table: Vec<Vec<Outcome>>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Rules {
+    /// Choices are worth 1, 2, 3, ... points, in the given order, and a loss,
+    /// draw or win is worth 0, 3 or 6 points.
+    pub fn cyclic(names: &[&str]) -> Result<Self> {
+        let n = names.len();
+        if !(3..=13).contains(&n) || n.is_multiple_of(2) {
+            return Err(anyhow!(
+                "need an odd number of choices from 3 to 13, not {n}"
+            ));
         }
+        let table = (0..n)
+            .map(|a| {
+                (0..n)
+                    .map(|b| match (n + b - a) % n {
+                        0 => Outcome::Draw,
+                        d if d % 2 == 1 => Outcome::Win,
+                        _ => Outcome::Lose,
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            choice_points: (1..=n as u64).collect(),
+            lose_points: 0,
+            draw_points: 3,
+            win_points: 6,
+            table,
+        })
+    }
+
+    /// Rock, Paper, Scissors.
+    pub fn classic() -> Self {
+        Self::cyclic(&["Rock", "Paper", "Scissors"])
+            .unwrap_or_else(|_| unreachable!())
     }
 
-    fn for_outcome(a: &Self, b: &Outcome) -> Self {
-        match (b, a) {
-            (Outcome::Win, Self::Rock) => Self::Paper,
-            (Outcome::Win, Self::Paper) => Self::Scissors,
-            (Outcome::Win, Self::Scissors) => Self::Rock,
+    /// Rock, Paper, Scissors, Spock, Lizard.
+    pub fn rpsls() -> Self {
+        Self::cyclic(&["Rock", "Paper", "Scissors", "Spock", "Lizard"])
+            .unwrap_or_else(|_| unreachable!())
+    }
 
-            (Outcome::Lose, Self::Rock) => Self::Scissors,
-            (Outcome::Lose, Self::Paper) => Self::Rock,
-            (Outcome::Lose, Self::Scissors) => Self::Paper,
+    pub fn with_choice_points(self, choice_points: &[u64]) -> Result<Self> {
+        if choice_points.len() != self.len() {
+            return Err(anyhow!(
+                "need points for {} choices, not {}",
+                self.len(),
+                choice_points.len()
+            ));
+        }
+        Ok(Self {
+            choice_points: choice_points.to_vec(),
+            ..self
+        })
+    }
 
-            (Outcome::Draw, a) => a.clone(),
+    pub fn with_outcome_points(self, lose: u64, draw: u64, win: u64) -> Self {
+        Self {
+            lose_points: lose,
+            draw_points: draw,
+            win_points: win,
+            ..self
         }
     }
 
-    fn from_misunderstanding_of_outcome(o: &Outcome) -> Self {
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn choices(&self) -> impl Iterator<Item = Choice> {
+        (0..self.len()).map(Choice)
+    }
+
+    /// The choice at the given position in the cycle, if there is one.
+    pub fn choice(&self, i: usize) -> Option<Choice> {
+        (i < self.len()).then_some(Choice(i))
+    }
+
+    pub fn name(&self, c: Choice) -> Result<&str> {
+        Ok(&self.names[self.index(c)?])
+    }
+
+    /// Outcome for the player choosing `b` against one choosing `a`.
+    pub fn outcome(&self, a: Choice, b: Choice) -> Result<Outcome> {
+        Ok(self.table[self.index(a)?][self.index(b)?])
+    }
+
+    pub fn choice_points(&self, c: Choice) -> Result<u64> {
+        Ok(self.choice_points[self.index(c)?])
+    }
+
+    pub fn outcome_points(&self, o: Outcome) -> u64 {
         match o {
-            Outcome::Lose => Self::Rock,
-            Outcome::Draw => Self::Paper,
-            Outcome::Win => Self::Scissors,
+            Outcome::Lose => self.lose_points,
+            Outcome::Draw => self.draw_points,
+            Outcome::Win => self.win_points,
         }
     }
-}
 
-struct Score {
-    a: u64,
-    b: u64,
-}
+    pub fn score(&self, a: Choice, b: Choice) -> Result<Score> {
+        self.index(a)?;
+        self.index(b)?;
+        Ok(self.score_of(a, b))
+    }
+
+    /// Choice leading to the given outcome against `a`, the highest scoring
+    /// one if there are several.
+    pub fn choice_for_outcome(
+        &self,
+        a: Choice,
+        o: Outcome,
+    ) -> Result<Choice> {
+        self.index(a)?;
+        Ok(self
+            .choices()
+            .filter(|b| self.table[a.0][b.0] == o)
+            .max_by_key(|b| (self.choice_points[b.0], std::cmp::Reverse(b.0)))
+            .unwrap_or_else(|| unreachable!("every outcome is possible")))
+    }
+
+    /// Highest scoring response to `a`, the first one among equals.
+    pub fn best_response(&self, a: Choice) -> Result<Choice> {
+        self.index(a)?;
+        Ok(self
+            .choices()
+            .max_by_key(|b| (self.score_of(a, *b).b, std::cmp::Reverse(b.0)))
+            .unwrap_or_else(|| unreachable!("there are choices")))
+    }
 
-impl Score {
-    fn add(s1: &Self, s2: &Self) -> Self {
+    /// Position of the choice, unless it's past the end of these rules,
+    /// having come from a larger game.
+    fn index(&self, c: Choice) -> Result<usize> {
+        match c.0 < self.len() {
+            true => Ok(c.0),
+            false => Err(anyhow!("invalid choice {} of {}", c.0, self.len())),
+        }
+    }
+
+    /// Score of choices known to be in range.
+    fn score_of(&self, a: Choice, b: Choice) -> Score {
+        let o = self.table[a.0][b.0];
         Score {
-            a: s1.a + s2.a,
-            b: s1.b + s2.b,
+            a: self.choice_points[a.0] + self.outcome_points(o.reverse()),
+            b: self.choice_points[b.0] + self.outcome_points(o),
         }
     }
 
-    fn from_choices(a: &Choice, b: &Choice) -> Self {
-        let base = Score {
-            a: Choice::points(a),
-            b: Choice::points(b),
-        };
-        let bonus = match (a, b) {
-            (Choice::Rock, Choice::Rock) => Score { a: 3, b: 3 },
-            (Choice::Rock, Choice::Paper) => Score { a: 0, b: 6 },
-            (Choice::Rock, Choice::Scissors) => Score { a: 6, b: 0 },
-
-            (Choice::Paper, Choice::Rock) => Score { a: 6, b: 0 },
-            (Choice::Paper, Choice::Paper) => Score { a: 3, b: 3 },
-            (Choice::Paper, Choice::Scissors) => Score { a: 0, b: 6 },
-
-            (Choice::Scissors, Choice::Rock) => Score { a: 0, b: 6 },
-            (Choice::Scissors, Choice::Paper) => Score { a: 6, b: 0 },
-            (Choice::Scissors, Choice::Scissors) => Score { a: 3, b: 3 },
-        };
-        Score::add(&base, &bonus)
+    /// Expected points of the mixed response, given as the probability of
+    /// each of our choices, against an opponent choosing with the given
+    /// probabilities, unless either hasn't got one per choice.
+    pub fn expected_points(
+        &self,
+        opponent: &[f64],
+        response: &[f64],
+    ) -> Result<f64> {
+        for (who, probabilities) in
+            [("opponent", opponent), ("response", response)]
+        {
+            if probabilities.len() != self.len() {
+                return Err(anyhow!(
+                    "need {} probabilities for the {who}, not {}",
+                    self.len(),
+                    probabilities.len()
+                ));
+            }
+        }
+        let mut points = 0.0;
+        for (a, p) in self.choices().zip(opponent) {
+            for (b, q) in self.choices().zip(response) {
+                points += p * q * self.score_of(a, b).b as f64;
+            }
+        }
+        Ok(points)
     }
+
+    /// Highest scoring fixed response against an opponent choosing with the
+    /// given probabilities, with its expected points. No mixed response can
+    /// do better, its expected points being an average of these.
+    pub fn best_fixed_response(
+        &self,
+        opponent: &[f64],
+    ) -> Result<(Choice, f64)> {
+        let mut best = (Choice(0), f64::NEG_INFINITY);
+        for b in self.choices() {
+            let mut response = vec![0.0; self.len()];
+            response[b.0] = 1.0;
+            let points = self.expected_points(opponent, &response)?;
+            if points > best.1 {
+                best = (b, points);
+            }
+        }
+        Ok(best)
+    }
+
+    fn parse_opponent(&self, s: &str) -> Result<Choice> {
+        self.letter_index(s, b'A')
+            .map(Choice)
+            .ok_or_else(|| anyhow!("invalid choice: {s:?}"))
+    }
+
+    /// Position of a letter from the 2nd column among our choice letters.
+    fn response_index(&self, s: &str) -> Result<usize> {
+        self.letter_index(s, b'Z' + 1 - self.len() as u8)
+            .ok_or_else(|| anyhow!("invalid strategy: {s:?}"))
+    }
+
+    fn letter_index(&self, s: &str, first: u8) -> Option<usize> {
+        match s.as_bytes() {
+            [c] if (first..first + self.len() as u8).contains(c) => {
+                Some((c - first) as usize)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Points of the opponent, `a`, and ours, `b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub a: u64,
+    pub b: u64,
 }
 
 struct Game {
     a: Choice,
-    b: Outcome,
+    // Letter from the 2nd column, its meaning being up for interpretation.
+    b: String,
 }
 
 impl Game {
-    fn parse(s: &str) -> Result<Self> {
+    fn parse(s: &str, rules: &Rules) -> Result<Self> {
         match s.split_whitespace().collect::<Vec<&str>>()[..] {
             [a, b] => {
-                let a = Choice::parse(a)?;
-                let b = Outcome::parse(b)?;
-                Ok(Self { a, b })
+                let a = rules.parse_opponent(a)?;
+                rules.response_index(b)?;
+                Ok(Self {
+                    a,
+                    b: b.to_string(),
+                })
             }
             _ => Err(anyhow!("invalid game: {s:?}")),
         }
     }
 }
 
+/// How the 2nd column of the guide is read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reading {
+    /// Our choice, the letters standing for the given choices in turn.
+    Choice(Vec<Choice>),
+    /// The outcome we need, `X`, `Y` or `Z` for lose, draw or win.
+    Outcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub a: Choice,
+    pub b: Choice,
+    /// Outcome for us.
+    pub outcome: Outcome,
+    pub score: Score,
+}
+
+/// Totals of a whole match, the counts of wins, draws and losses being ours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchReport {
+    pub reading: Reading,
+    pub a: u64,
+    pub b: u64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl MatchReport {
+    /// Our points minus the opponent's.
+    pub fn margin(&self) -> i64 {
+        self.b as i64 - self.a as i64
+    }
+
+    /// Outcome of the match for us, by total points.
+    pub fn outcome(&self) -> Outcome {
+        match self.b.cmp(&self.a) {
+            std::cmp::Ordering::Greater => Outcome::Win,
+            std::cmp::Ordering::Equal => Outcome::Draw,
+            std::cmp::Ordering::Less => Outcome::Lose,
+        }
+    }
+}
+
+/// How the guide fares against the best responses to the opponent's moves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterReport {
+    /// How often the opponent made each choice.
+    pub distribution: Vec<f64>,
+    /// Best fixed response to the distribution.
+    pub fixed: Choice,
+    /// Expected points per round of the fixed response.
+    pub fixed_expected: f64,
+    /// Points from playing the fixed response every round.
+    pub fixed_total: u64,
+    /// Points from playing the best response to each round.
+    pub best_total: u64,
+    /// Points from following the guide.
+    pub guide_total: u64,
+    /// Rounds in which the guide doesn't play the best response.
+    pub suboptimal: Vec<usize>,
+}
+
 pub struct Data {
+    rules: Rules,
     games: Vec<Game>,
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
+        Self::load_with_rules(input, Rules::default())
+    }
+
+    pub fn load_with_rules(
+        input: &std::path::Path,
+        rules: Rules,
+    ) -> Result<Self> {
         let file = std::fs::File::open(input)?;
+        Self::read(std::io::BufReader::new(file), rules)
+    }
+
+    pub fn read<R: BufRead>(reader: R, rules: Rules) -> Result<Self> {
         let mut games: Vec<Game> = Vec::new();
-        for line_result in std::io::BufReader::new(file).lines() {
+        for line_result in reader.lines() {
             let line = line_result?;
-            games.push(Game::parse(&line)?);
+            games.push(Game::parse(&line, &rules)?);
         }
-        Ok(Self { games })
+        Ok(Self { rules, games })
     }
 
+    /// The 2nd column is our choice.
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<u64> {
-        Ok(self
-            .games
-            .iter()
-            .map(|Game { a, b }| {
-                Score::from_choices(
-                    a,
-                    &Choice::from_misunderstanding_of_outcome(b),
-                )
-            })
-            .map(|Score { b, .. }| b)
-            .sum())
+        let choices = self.rules.choices().collect();
+        Ok(self.report(Reading::Choice(choices))?.b)
     }
 
+    /// The 2nd column is the outcome we need.
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<u64> {
-        Ok(self
-            .games
-            .iter()
-            .map(|Game { a, b }| {
-                Score::from_choices(a, &Choice::for_outcome(a, b))
+        Ok(self.report(Reading::Outcome)?.b)
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Each round of the guide played under the given reading, unless it
+    /// doesn't fit the rules.
+    pub fn rounds<'a>(
+        &'a self,
+        reading: &'a Reading,
+    ) -> Result<impl Iterator<Item = Result<Round>> + 'a> {
+        self.check(reading)?;
+        Ok(self.games.iter().map(move |Game { a, b }| {
+            let b = match reading {
+                Reading::Choice(choices) => {
+                    choices[self.rules.response_index(b)?]
+                }
+                Reading::Outcome => {
+                    self.rules.choice_for_outcome(*a, Outcome::parse(b)?)?
+                }
+            };
+            Ok(Round {
+                a: *a,
+                b,
+                outcome: self.rules.outcome(*a, b)?,
+                score: self.rules.score(*a, b)?,
             })
-            .map(|Score { b, .. }| b)
-            .sum())
+        }))
+    }
+
+    /// Checks that a reading has one choice per letter, each of them one of
+    /// the rules' choices.
+    fn check(&self, reading: &Reading) -> Result<()> {
+        let Reading::Choice(choices) = reading else {
+            return Ok(());
+        };
+        let n = self.rules.len();
+        if choices.len() != n {
+            return Err(anyhow!(
+                "need {n} choices to read the guide, not {}",
+                choices.len()
+            ));
+        }
+        for c in choices {
+            self.rules.index(*c)?;
+        }
+        Ok(())
+    }
+
+    /// Totals of the whole guide played under the given reading.
+    pub fn report(&self, reading: Reading) -> Result<MatchReport> {
+        let mut report = MatchReport {
+            reading: reading.clone(),
+            a: 0,
+            b: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        };
+        for round_result in self.rounds(&reading)? {
+            let round = round_result?;
+            report.a += round.score.a;
+            report.b += round.score.b;
+            match round.outcome {
+                Outcome::Win => report.wins += 1,
+                Outcome::Draw => report.draws += 1,
+                Outcome::Lose => report.losses += 1,
+            }
+        }
+        Ok(report)
+    }
+
+    /// Every way of reading the guide: each assignment of our choices to the
+    /// letters, in lexicographic order, then the outcomes if the letters are
+    /// `X`, `Y` and `Z`. There are n! assignments for n choices, so this is
+    /// only practical for small games.
+    pub fn readings(&self) -> impl Iterator<Item = Reading> {
+        let n = self.rules.len();
+        let outcome = (n == 3).then_some(Reading::Outcome);
+        std::iter::successors(Some((0..n).collect()), |order: &Vec<usize>| {
+            next_permutation(order)
+        })
+        .map(|order: Vec<usize>| {
+            Reading::Choice(order.into_iter().map(Choice).collect())
+        })
+        .chain(outcome)
     }
+
+    /// Reports for every reading of the guide, see [`Data::readings`].
+    pub fn explore(&self) -> Result<Vec<MatchReport>> {
+        self.readings()
+            .map(|reading| self.report(reading))
+            .collect()
+    }
+
+    /// Reading giving us the most points, the first one among equals.
+    pub fn best_score(&self) -> Result<MatchReport> {
+        self.best_by_key(|report| report.b as i64)
+    }
+
+    /// Reading giving us the widest lead over the opponent, the first one
+    /// among equals.
+    pub fn best_margin(&self) -> Result<MatchReport> {
+        self.best_by_key(MatchReport::margin)
+    }
+
+    /// Number of times the opponent made each choice.
+    pub fn opponent_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.rules.len()];
+        for game in self.games.iter() {
+            counts[game.a.0] += 1;
+        }
+        counts
+    }
+
+    /// Share of the rounds in which the opponent made each choice.
+    pub fn opponent_distribution(&self) -> Result<Vec<f64>> {
+        if self.games.is_empty() {
+            return Err(anyhow!("empty guide"));
+        }
+        let n = self.games.len() as f64;
+        Ok(self
+            .opponent_counts()
+            .into_iter()
+            .map(|count| count as f64 / n)
+            .collect())
+    }
+
+    /// Compares the guide, under the given reading, with the best responses
+    /// to the opponent, round by round and all in all.
+    pub fn counter(&self, reading: &Reading) -> Result<CounterReport> {
+        let distribution = self.opponent_distribution()?;
+        let (fixed, fixed_expected) =
+            self.rules.best_fixed_response(&distribution)?;
+        let mut report = CounterReport {
+            distribution,
+            fixed,
+            fixed_expected,
+            fixed_total: 0,
+            best_total: 0,
+            guide_total: 0,
+            suboptimal: Vec::new(),
+        };
+        for (i, round_result) in self.rounds(reading)?.enumerate() {
+            let round = round_result?;
+            let best = self
+                .rules
+                .score(round.a, self.rules.best_response(round.a)?)?;
+            report.fixed_total += self.rules.score(round.a, fixed)?.b;
+            report.best_total += best.b;
+            report.guide_total += round.score.b;
+            if round.score.b < best.b {
+                report.suboptimal.push(i + 1);
+            }
+        }
+        Ok(report)
+    }
+
+    fn best_by_key(
+        &self,
+        key: impl Fn(&MatchReport) -> i64,
+    ) -> Result<MatchReport> {
+        let mut best: Option<MatchReport> = None;
+        for report in self.explore()? {
+            if best.as_ref().is_none_or(|b| key(&report) > key(b)) {
+                best = Some(report);
+            }
+        }
+        best.ok_or_else(|| anyhow!("no way to read the guide"))
+    }
+}
+
+/// The lexicographically next arrangement, if any.
+fn next_permutation(order: &[usize]) -> Option<Vec<usize>> {
+    let i = order.windows(2).rposition(|w| w[0] < w[1])?;
+    let j = order.iter().rposition(|x| *x > order[i])?;
+    let mut next = order.to_vec();
+    next.swap(i, j);
+    next[i + 1..].reverse();
+    Some(next)
+}
+
+#[test]
+fn cyclic_rules() {
+    for rules in [Rules::classic(), Rules::rpsls()] {
+        let n = rules.len();
+        for a in rules.choices() {
+            let wins = rules
+                .choices()
+                .filter(|b| rules.outcome(a, *b).unwrap() == Outcome::Win)
+                .count();
+            assert_eq!((n - 1) / 2, wins);
+            assert_eq!(Outcome::Draw, rules.outcome(a, a).unwrap());
+        }
+    }
+    let r = Rules::classic();
+    assert_eq!(
+        Outcome::Win,
+        r.outcome(Choice::ROCK, Choice::PAPER).unwrap()
+    );
+    assert_eq!(
+        Outcome::Win,
+        r.outcome(Choice::SCISSORS, Choice::ROCK).unwrap()
+    );
+    assert_eq!(
+        Outcome::Lose,
+        r.outcome(Choice::ROCK, Choice::SCISSORS).unwrap()
+    );
+    let r = Rules::rpsls();
+    let [rock, paper, scissors, spock, lizard] =
+        [0, 1, 2, 3, 4].map(|i| r.choice(i).unwrap());
+    assert_eq!(Outcome::Win, r.outcome(lizard, rock).unwrap());
+    assert_eq!(Outcome::Win, r.outcome(paper, lizard).unwrap());
+    assert_eq!(Outcome::Win, r.outcome(scissors, spock).unwrap());
+    assert_eq!(Outcome::Win, r.outcome(rock, spock).unwrap());
+    assert!(Rules::cyclic(&["Rock", "Paper"]).is_err());
+    // Spock and Lizard aren't part of the classic game.
+    let classic = Rules::classic();
+    assert_eq!(None, classic.choice(3));
+    assert_eq!(Ok("Spock"), r.name(spock).map_err(|e| e.to_string()));
+    assert_eq!(
+        Err("invalid choice 3 of 3".to_string()),
+        classic.outcome(spock, rock).map_err(|e| e.to_string())
+    );
+    assert!(classic.score(rock, lizard).is_err());
+    assert!(classic.best_response(lizard).is_err());
+}
+
+#[test]
+fn rpsls_guide() {
+    // A/V Rock, B/W Paper, C/X Scissors, D/Y Spock, E/Z Lizard
+    let guide = "A Y\nE V\nD W\n";
+    let data = Data::read(guide.as_bytes(), Rules::rpsls()).unwrap();
+    // Spock beats Rock, Rock beats Lizard, Paper beats Spock.
+    assert_eq!((4 + 6) + (1 + 6) + (2 + 6), data.solve1().unwrap());
+    assert!(Data::read("A U\n".as_bytes(), Rules::rpsls()).is_err());
+    assert!(Data::read("A W\n".as_bytes(), Rules::classic()).is_err());
+    let rules = Rules::classic()
+        .with_choice_points(&[10, 20, 30])
+        .unwrap()
+        .with_outcome_points(1, 2, 3);
+    let data = Data::read("A Y\n".as_bytes(), rules).unwrap();
+    assert_eq!(20 + 3, data.solve1().unwrap());
+    assert_eq!(10 + 2, data.solve2().unwrap());
+}
+
+#[test]
+fn match_report() {
+    // Rock v Paper, Paper v Rock, Scissors v Scissors
+    let data =
+        Data::read("A Y\nB X\nC Z\n".as_bytes(), Rules::classic()).unwrap();
+    let r = data.report(Reading::Choice([0, 1, 2].map(Choice).to_vec()));
+    let r = r.unwrap();
+    assert_eq!((15, 15), (r.a, r.b));
+    assert_eq!((1, 1, 1), (r.wins, r.draws, r.losses));
+    assert_eq!(Outcome::Draw, r.outcome());
+    // Draw with Rock, lose with Rock, win with Rock
+    let r = data.report(Reading::Outcome).unwrap();
+    assert_eq!((4 + 8 + 3, 4 + 1 + 7), (r.a, r.b));
+    assert_eq!(Outcome::Lose, r.outcome());
+    let rounds: Vec<Round> = data
+        .rounds(&Reading::Outcome)
+        .unwrap()
+        .collect::<Result<_>>()
+        .unwrap();
+    assert_eq!(Choice::ROCK, rounds[1].b);
+    assert_eq!(Score { a: 8, b: 1 }, rounds[1].score);
+    let err = |reading| data.rounds(&reading).err().unwrap().to_string();
+    assert_eq!(
+        "need 3 choices to read the guide, not 0",
+        err(Reading::Choice(vec![]))
+    );
+    assert_eq!(
+        "invalid choice 3 of 3",
+        err(Reading::Choice([0, 1, 3].map(Choice).to_vec()))
+    );
+}
+
+#[test]
+fn guide_readings() {
+    let data =
+        Data::read("A Y\nB X\nC Z\n".as_bytes(), Rules::classic()).unwrap();
+    let reports = data.explore().unwrap();
+    assert_eq!(6 + 1, reports.len());
+    assert_eq!(data.solve1().unwrap(), reports[0].b);
+    assert_eq!(data.solve2().unwrap(), reports[6].b);
+    // X Scissors, Y Paper, Z Rock wins every round.
+    let best = data.best_score().unwrap();
+    let [r, p, s] = [Choice::ROCK, Choice::PAPER, Choice::SCISSORS];
+    assert_eq!(Reading::Choice(vec![s, p, r]), best.reading);
+    assert_eq!((3, 0, 0), (best.wins, best.draws, best.losses));
+    assert_eq!(best, data.best_margin().unwrap());
+    assert_eq!(-18, reports.iter().map(MatchReport::margin).min().unwrap());
+    let data = Data::read("A Y\n".as_bytes(), Rules::rpsls()).unwrap();
+    assert_eq!(120, data.readings().count());
+}
+
+#[test]
+fn counter_strategy() {
+    // Rock twice, then Scissors
+    let data =
+        Data::read("A Y\nA X\nC X\n".as_bytes(), Rules::classic()).unwrap();
+    assert_eq!(vec![2, 0, 1], data.opponent_counts());
+    let r = Rules::classic();
+    assert_eq!(Choice::PAPER, r.best_response(Choice::ROCK).unwrap());
+    assert_eq!(Choice::ROCK, r.best_response(Choice::SCISSORS).unwrap());
+    // Uniform play against a uniform opponent: 2 + 3 points on average.
+    let third = [1.0 / 3.0; 3];
+    assert!((r.expected_points(&third, &third).unwrap() - 5.0).abs() < 1e-9);
+    assert!(r.expected_points(&third, &[1.0, 0.0]).is_err());
+    assert!(r.best_fixed_response(&[0.5; 4]).is_err());
+    let report = data.counter(&Reading::Outcome).unwrap();
+    assert_eq!(Choice::PAPER, report.fixed);
+    assert!((report.fixed_expected - (8.0 * 2.0 + 2.0) / 3.0).abs() < 1e-9);
+    assert_eq!(8 + 8 + 2, report.fixed_total);
+    assert_eq!(8 + 8 + 7, report.best_total);
+    // Draw with Rock, lose with Scissors, lose with Paper
+    assert_eq!(4 + 3 + 2, report.guide_total);
+    assert_eq!(vec![1, 2, 3], report.suboptimal);
+    assert!(data.counter(&Reading::Choice(vec![Choice::ROCK])).is_err());
+    assert!(Data::read("".as_bytes(), Rules::classic())
+        .unwrap()
+        .counter(&Reading::Outcome)
+        .is_err());
 }
@@ -1,125 +1,610 @@
-use std::collections::HashSet;
 use std::io::BufRead; // lines()
+use std::ops::RangeInclusive;
 
 use anyhow::{anyhow, Result};
 
+/// Items in order of priority, from 1 upwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    items: Vec<char>,
+    // Priority of each ASCII item, 0 if not in the alphabet, to spare the
+    // search through `items` in the common case.
+    ascii: [u8; 128],
+}
+
+impl Default for Alphabet {
+    /// `a` to `z`, then `A` to `Z`.
+    fn default() -> Self {
+        let letters: String = ('a'..='z').chain('A'..='Z').collect();
+        Self::new(&letters).unwrap_or_else(|_| unreachable!())
+    }
+}
+
+impl Alphabet {
+    /// At most 64 distinct items.
+    pub fn new(items: &str) -> Result<Self> {
+        Self {
+            items: Vec::new(),
+            ascii: [0; 128],
+        }
+        .extend(items)
+    }
+
+    /// This alphabet followed by the given items, which take the next
+    /// priorities in order.
+    pub fn extend(mut self, items: &str) -> Result<Self> {
+        for c in items.chars() {
+            if self.priority(c).is_some() {
+                return Err(anyhow!("duplicate item {c:?} in alphabet"));
+            }
+            if self.items.len() == u64::BITS as usize {
+                return Err(anyhow!(
+                    "more than {} items in alphabet",
+                    u64::BITS
+                ));
+            }
+            self.items.push(c);
+            if c.is_ascii() {
+                self.ascii[c as usize] = self.items.len() as u8;
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn priority(&self, c: char) -> Option<u32> {
+        if c.is_ascii() {
+            return Some(self.ascii[c as usize] as u32).filter(|p| *p != 0);
+        }
+        let (p, _) = (1..).zip(self.items.iter()).find(|(_, x)| **x == c)?;
+        Some(p)
+    }
+
+    pub fn item(&self, priority: u32) -> Option<char> {
+        self.items.get((priority as usize).checked_sub(1)?).copied()
+    }
+
+    pub fn parse(&self, s: &str) -> Result<ItemSet> {
+        let mut set = ItemSet::EMPTY;
+        for c in s.chars() {
+            let p = self
+                .priority(c)
+                .ok_or_else(|| anyhow!("invalid item {c:?}"))?;
+            set.insert(p)?;
+        }
+        Ok(set)
+    }
+
+    /// Items of the set in order of priority.
+    pub fn items(&self, set: ItemSet) -> String {
+        set.priorities().filter_map(|p| self.item(p)).collect()
+    }
+}
+
+/// Set of item priorities, from 1 to 64, each being a bit of a word.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    pub const EMPTY: Self = Self(0);
+    pub const FULL: Self = Self(!0);
+
+    /// Adds the item of the given priority, unless it isn't from 1 to 64.
+    pub fn insert(&mut self, priority: u32) -> Result<()> {
+        if !(1..=u64::BITS).contains(&priority) {
+            return Err(anyhow!("invalid priority {priority}"));
+        }
+        self.0 |= 1 << (priority - 1);
+        Ok(())
+    }
+
+    pub fn contains(&self, priority: u32) -> bool {
+        (1..=u64::BITS).contains(&priority)
+            && self.0 & 1 << (priority - 1) != 0
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn priorities(&self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            let p = bits.trailing_zeros();
+            if p == u64::BITS {
+                return None;
+            }
+            bits &= bits - 1;
+            Some(p + 1)
+        })
+    }
+
+    /// Sum of the priorities of the items.
+    pub fn priority(&self) -> u32 {
+        self.priorities().sum()
+    }
+}
+
+impl std::ops::BitOr for ItemSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl std::ops::BitAnd for ItemSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Sack {
-    compartment_1: HashSet<char>,
-    compartment_2: HashSet<char>,
+    line: usize,
+    items: Vec<char>,
+    compartment_1: ItemSet,
+    compartment_2: ItemSet,
 }
 
 impl Sack {
-    fn contents(&self) -> HashSet<char> {
-        self.compartment_1
-            .union(&self.compartment_2)
+    fn contents(&self) -> ItemSet {
+        self.compartment_1 | self.compartment_2
+    }
+
+    fn misplaced(&self) -> ItemSet {
+        self.compartment_1 & self.compartment_2
+    }
+
+    /// Fewest moves leaving every item type in one compartment only, both
+    /// compartments keeping their size, unless there's no such arrangement.
+    fn repair(&self, alphabet: &Alphabet) -> Option<Repair> {
+        let (left, right) = self.items.split_at(self.items.len() / 2);
+        let count = |items: &[char], p| {
+            items
+                .iter()
+                .filter(|c| alphabet.priority(**c) == Some(p))
+                .count()
+        };
+        let kinds: Vec<(u32, usize, usize)> = self
+            .contents()
+            .priorities()
+            .map(|p| (p, count(left, p), count(right, p)))
+            .collect();
+        // Cheapest choice of item types for the 1st compartment, by the
+        // number of its items, each type costing its items in the 2nd. As
+        // many items then have to leave it, so the moves are twice that.
+        let mut best: Vec<Option<(usize, ItemSet)>> =
+            vec![None; left.len() + 1];
+        best[0] = Some((0, ItemSet::EMPTY));
+        for (p, l, r) in kinds.iter().copied() {
+            for size in (l + r..=left.len()).rev() {
+                let Some((cost, mut kept)) = best[size - l - r] else {
+                    continue;
+                };
+                if best[size].is_none_or(|(c, _)| cost + r < c) {
+                    kept.insert(p).ok()?;
+                    best[size] = Some((cost + r, kept));
+                }
+            }
+        }
+        let (cost, kept) = best[left.len()]?;
+        let kept = |c: &&char| {
+            alphabet.priority(**c).is_some_and(|p| kept.contains(p))
+        };
+        let to_left: String = right.iter().filter(kept).collect();
+        let to_right: String = left.iter().filter(|c| !kept(c)).collect();
+        let items = left
+            .iter()
+            .filter(kept)
             .copied()
-            .collect::<HashSet<char>>()
+            .chain(to_left.chars())
+            .chain(right.iter().filter(|c| !kept(c)).copied())
+            .chain(to_right.chars())
+            .collect();
+        Some(Repair {
+            line: self.line,
+            moves: 2 * cost,
+            to_left,
+            to_right,
+            items,
+        })
     }
+}
+
+/// Items to move between the compartments of a sack to fix it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repair {
+    pub line: usize,
+    /// Number of items moved, `to_left` and `to_right` together.
+    pub moves: usize,
+    /// Items moved from the 2nd compartment to the 1st.
+    pub to_left: String,
+    /// Items moved from the 1st compartment to the 2nd.
+    pub to_right: String,
+    /// Sack after the moves, in the input format.
+    pub items: String,
+}
 
-    fn intersection(&self, s2: &Self) -> HashSet<char> {
-        let c1 = self.contents();
-        let c2 = s2.contents();
-        c1.intersection(&c2).copied().collect::<HashSet<char>>()
+/// Repairs for every sack with items in both compartments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairPlan {
+    pub repairs: Vec<Repair>,
+    /// Lines of the sacks which can't be fixed.
+    pub unfixable: Vec<usize>,
+    // Every sack in the input format, repaired where possible.
+    lines: Vec<String>,
+}
+
+impl RepairPlan {
+    pub fn moves(&self) -> usize {
+        self.repairs.iter().map(|repair| repair.moves).sum()
     }
 }
 
+/// The whole inventory after the repairs, one sack per line.
+impl std::fmt::Display for RepairPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in self.lines.iter() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Sack or group not sharing exactly one item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// Items in both compartments of the sack on the given line.
+    Sack { line: usize, shared: String },
+    /// Items shared by the group of sacks on the given lines.
+    Group {
+        lines: RangeInclusive<usize>,
+        shared: String,
+    },
+    /// Sacks left over after the last full group.
+    Trailing { lines: RangeInclusive<usize> },
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items = |shared: &String| match shared.chars().count() {
+            0 => "no items".to_string(),
+            n => format!("{n} items ({shared})"),
+        };
+        match self {
+            Self::Sack { line, shared } => write!(
+                f,
+                "sack on line {line} has {} in both compartments",
+                items(shared)
+            ),
+            Self::Group { lines, shared } => write!(
+                f,
+                "group on lines {} to {} shares {}",
+                lines.start(),
+                lines.end(),
+                items(shared)
+            ),
+            Self::Trailing { lines } => write!(
+                f,
+                "trailing group of {} sacks on lines {} to {}",
+                lines.end() + 1 - lines.start(),
+                lines.start(),
+                lines.end()
+            ),
+        }
+    }
+}
+
+/// Lines of a group of sacks and the items they all have.
+type Group = (RangeInclusive<usize>, ItemSet);
+
 pub struct Data {
+    alphabet: Alphabet,
     sacks: Vec<Sack>,
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
+        Self::load_with_alphabet(input, Alphabet::default())
+    }
+
+    pub fn load_with_alphabet(
+        input: &std::path::Path,
+        alphabet: Alphabet,
+    ) -> Result<Self> {
         let file = std::fs::File::open(input)?;
+        Self::read(std::io::BufReader::new(file), alphabet)
+    }
+
+    pub fn read<R: BufRead>(reader: R, alphabet: Alphabet) -> Result<Self> {
         let mut sacks = Vec::new();
-        for line_result in std::io::BufReader::new(file).lines() {
+        for (ln, line_result) in (1..).zip(reader.lines()) {
             let line = line_result?;
-            let len = line.len();
-            if len < 2 || len % 2 != 0 {
+            let items: Vec<char> = line.chars().collect();
+            let len = items.len();
+            if len < 2 || !len.is_multiple_of(2) {
                 return Err(anyhow!(
                     "invalid number of items ({len}) in {line:?}"
                 ));
             }
-            let mid = len / 2;
-            let c1: HashSet<char> =
-                line[0..mid].to_string().chars().collect();
-            let c2: HashSet<char> = line[mid..].to_string().chars().collect();
+            let mut compartments = [ItemSet::EMPTY; 2];
+            for (col, c) in (1..).zip(items.iter()) {
+                let p = alphabet.priority(*c).ok_or_else(|| {
+                    anyhow!("invalid item {c:?} in line {ln}, column {col}")
+                })?;
+                compartments[(col - 1) / (len / 2)].insert(p)?;
+            }
+            let [compartment_1, compartment_2] = compartments;
             sacks.push(Sack {
-                compartment_1: c1,
-                compartment_2: c2,
+                line: ln,
+                items,
+                compartment_1,
+                compartment_2,
             });
         }
-        Ok(Self { sacks })
+        Ok(Self { alphabet, sacks })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<u64> {
         let mut total = 0;
-        for Sack {
-            compartment_1: c1,
-            compartment_2: c2,
-        } in self.sacks.iter()
-        {
-            let intersection: HashSet<&char> = c1.intersection(c2).collect();
-            let intersection = if intersection.len() != 1 {
-                return Err(anyhow!(
-                    "invalid number of intersections: {}",
-                    intersection.len()
-                ));
-            } else {
-                **intersection.iter().next().unwrap()
-            };
-            // unwrap OK because we checked len.
-            let p = priority(intersection);
-            total += p;
+        for sack in self.sacks.iter() {
+            let shared = sack.misplaced();
+            if shared.len() != 1 {
+                let problem = Problem::Sack {
+                    line: sack.line,
+                    shared: self.alphabet.items(shared),
+                };
+                return Err(anyhow!("{problem}"));
+            }
+            total += shared.priority();
         }
         Ok(total as u64)
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<u64> {
-        let mut groups = Vec::new();
-        let mut group_buf = Vec::new();
-        let mut i = 1;
-        for sack in self.sacks.iter() {
-            group_buf.push(sack);
-            if i % 3 == 0 {
-                groups.push(group_buf.clone());
-                group_buf.clear();
-            };
-            i += 1;
+        self.badges(3)
+    }
+
+    /// Sum of the priorities of the one item shared by each group of the
+    /// given number of consecutive sacks.
+    pub fn badges(&self, group_size: usize) -> Result<u64> {
+        let (groups, trailing) = self.groups(group_size)?;
+        if let Some(problem) = trailing {
+            return Err(anyhow!("{problem}"));
         }
         let mut total = 0;
-        for group in groups {
-            let (s1, s2, s3) = match &group[..] {
-                [s1, s2, s3] => (s1, s2, s3),
-                _ => unreachable!(),
-            };
-            let intersection: HashSet<char> = s1
-                .intersection(s2)
-                .intersection(&s3.contents())
-                .copied()
-                .collect();
-            if intersection.len() != 1 {
-                return Err(anyhow!(
-                    "invalid number of intersections: {}",
-                    intersection.len()
-                ));
+        for (lines, shared) in groups {
+            if shared.len() != 1 {
+                let shared = self.alphabet.items(shared);
+                let problem = Problem::Group { lines, shared };
+                return Err(anyhow!("{problem}"));
             }
-            let intersection = intersection.iter().next().unwrap();
-            let p = priority(*intersection);
-            total += p;
+            total += shared.priority();
         }
         Ok(total as u64)
     }
+
+    /// Every sack and group of sacks which doesn't share exactly one item,
+    /// in order of their lines.
+    pub fn diagnose(&self, group_size: usize) -> Result<Vec<Problem>> {
+        let (groups, trailing) = self.groups(group_size)?;
+        let sacks = self
+            .sacks
+            .iter()
+            .filter(|sack| sack.misplaced().len() != 1)
+            .map(|sack| Problem::Sack {
+                line: sack.line,
+                shared: self.alphabet.items(sack.misplaced()),
+            });
+        let groups = groups
+            .into_iter()
+            .filter(|(_, shared)| shared.len() != 1)
+            .map(|(lines, shared)| Problem::Group {
+                lines,
+                shared: self.alphabet.items(shared),
+            });
+        let mut problems: Vec<Problem> =
+            sacks.chain(groups).chain(trailing).collect();
+        problems.sort_by_key(|problem| match problem {
+            Problem::Sack { line, .. } => (*line, 1),
+            Problem::Group { lines, .. } | Problem::Trailing { lines } => {
+                (*lines.start(), 0)
+            }
+        });
+        Ok(problems)
+    }
+
+    /// Fewest item moves between compartments leaving no item type in both
+    /// compartments of any sack.
+    pub fn repair(&self) -> RepairPlan {
+        let mut plan = RepairPlan::default();
+        for sack in self.sacks.iter() {
+            if sack.misplaced().is_empty() {
+                plan.lines.push(sack.items.iter().collect());
+                continue;
+            }
+            match sack.repair(&self.alphabet) {
+                Some(repair) => {
+                    plan.lines.push(repair.items.clone());
+                    plan.repairs.push(repair);
+                }
+                None => {
+                    plan.lines.push(sack.items.iter().collect());
+                    plan.unfixable.push(sack.line);
+                }
+            }
+        }
+        plan
+    }
+
+    /// The lines of each full group with the items its sacks all have, and
+    /// the sacks left over, if any.
+    fn groups(
+        &self,
+        group_size: usize,
+    ) -> Result<(Vec<Group>, Option<Problem>)> {
+        if group_size == 0 {
+            return Err(anyhow!("invalid group size: 0"));
+        }
+        let chunks = self.sacks.chunks_exact(group_size);
+        let trailing = match chunks.remainder() {
+            [] => None,
+            [first, .., last] | [first @ last] => Some(Problem::Trailing {
+                lines: first.line..=last.line,
+            }),
+        };
+        let groups = chunks
+            .map(|group| {
+                let lines = group[0].line..=group[group.len() - 1].line;
+                let shared = group
+                    .iter()
+                    .map(Sack::contents)
+                    .fold(ItemSet::FULL, ItemSet::intersection);
+                (lines, shared)
+            })
+            .collect();
+        Ok((groups, trailing))
+    }
+}
+
+#[test]
+fn item_sets() {
+    let letters = Alphabet::default();
+    let a = letters.parse("vJrwpWtwJgWr").unwrap();
+    let b = letters.parse("hcsFMMfFFhFp").unwrap();
+    assert_eq!("p", letters.items(a & b));
+    assert_eq!(16, (a & b).priority());
+    assert_eq!("gprtvwJW", letters.items(a));
+    assert_eq!(8, a.len());
+    assert_eq!(14, (a | b).len());
+    assert!(a.contains(36) && !a.contains(10) && !a.contains(0));
+    assert!(ItemSet::EMPTY.is_empty());
+    let mut set = ItemSet::EMPTY;
+    assert!(set.insert(64).is_ok() && set.contains(64));
+    assert!(set.insert(0).is_err() && set.insert(65).is_err());
+    assert_eq!(1, set.len());
+    assert!(letters.parse("ab1").is_err());
+    let all: String = ('a'..='z').chain('A'..='Z').collect();
+    assert_eq!(all, letters.items(letters.parse(&all).unwrap()));
+}
+
+#[test]
+fn item_alphabets() {
+    let letters = Alphabet::default();
+    assert_eq!(
+        (Some(1), Some(27), None),
+        (
+            letters.priority('a'),
+            letters.priority('A'),
+            letters.priority('1')
+        )
+    );
+    assert_eq!(
+        (Some('z'), None, None),
+        (letters.item(26), letters.item(0), letters.item(53))
+    );
+    let extended = letters.clone().extend("0123456789").unwrap();
+    assert_eq!(Some(53), extended.priority('0'));
+    assert!(extended.clone().extend("a").is_err());
+    assert!(extended.extend("!@#$%^&*()").is_err());
+    let greek = Alphabet::new("αβγ").unwrap();
+    assert_eq!(Some(2), greek.priority('β'));
+    let data = Data::read("αββγ\nγααβ\n".as_bytes(), greek).unwrap();
+    assert_eq!(2 + 1, data.solve1().unwrap());
+    let err = Data::read("vJrw\nab1c".as_bytes(), letters).err().unwrap();
+    assert_eq!("invalid item '1' in line 2, column 3", err.to_string());
+}
+
+#[test]
+fn group_diagnostics() {
+    let sacks = "abca\nabAb\nbaBa\nyzyz\nBc";
+    let data = Data::read(sacks.as_bytes(), Alphabet::default()).unwrap();
+    let problems = data.diagnose(3).unwrap();
+    let messages: Vec<String> =
+        problems.iter().map(Problem::to_string).collect();
+    assert_eq!(
+        vec![
+            "group on lines 1 to 3 shares 2 items (ab)",
+            "trailing group of 2 sacks on lines 4 to 5",
+            "sack on line 4 has 2 items (yz) in both compartments",
+            "sack on line 5 has no items in both compartments",
+        ],
+        messages
+    );
+    assert_eq!(
+        "trailing group of 1 sacks on lines 5 to 5",
+        data.badges(2).unwrap_err().to_string()
+    );
+    let data = Data::read("abca\nabAb\nbaBa".as_bytes(), Alphabet::default())
+        .unwrap();
+    assert_eq!(
+        Problem::Group {
+            lines: 1..=3,
+            shared: "ab".to_string()
+        },
+        data.diagnose(3).unwrap()[0]
+    );
+    assert_eq!(1 + 2 + 1, data.solve1().unwrap());
+    assert!(data.badges(1).is_err());
+    assert!(data.badges(0).is_err());
 }
 
-fn priority(c: char) -> u32 {
-    let init_lower = 'a' as u32;
-    let init_upper = 'A' as u32;
-    let (init_old, init_new) = match c {
-        'a'..='z' => (init_lower, 1),
-        'A'..='Z' => (init_upper, 27),
-        _ => panic!("unexpected character: {c:?}"),
-    };
-    let n = c as u32;
-    init_new + (n - init_old)
+#[test]
+fn repair_plan() {
+    let sacks = "vJrwpWtwJgWrhcsFMfFFhFpM\naabbab\nabcd\naaab";
+    let data = Data::read(sacks.as_bytes(), Alphabet::default()).unwrap();
+    let plan = data.repair();
+    // Swapping the p on the right for a lone item on the left is cheapest.
+    let repair = &plan.repairs[0];
+    assert_eq!(
+        (1, 2, "p", "v"),
+        (
+            repair.line,
+            repair.moves,
+            &repair.to_left[..],
+            &repair.to_right[..]
+        )
+    );
+    // An odd number of a's can't be split evenly by type.
+    assert_eq!(vec![4], plan.unfixable);
+    assert_eq!(2, plan.repairs.len());
+    assert_eq!(2 + 2, plan.moves());
+    let fixed =
+        Data::read(plan.to_string().as_bytes(), Alphabet::default()).unwrap();
+    let problems = fixed.diagnose(1).unwrap();
+    assert!(problems.iter().all(|problem| match problem {
+        Problem::Sack { line, shared } => shared.is_empty() || *line == 4,
+        _ => true,
+    }));
+    let lines: Vec<String> =
+        plan.to_string().lines().map(String::from).collect();
+    assert_eq!(
+        vec!["JrwpWtwJgWrphcsFMfFFhFMv", "aaabbb", "abcd", "aaab"],
+        lines
+    );
 }
@@ -1,107 +1,204 @@
 use std::io::BufRead; // lines()
+use std::ops::RangeInclusive;
 
 use anyhow::{anyhow, Result};
 
+use crate::interval::{self, IntervalSet};
+
+/// An elf by the line of its assignment and its position within the line,
+/// both counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Elf {
+    pub line: usize,
+    pub slot: usize,
+}
+
+/// Sections covered across the whole list of assignments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    /// Sections assigned to at least one elf.
+    pub covered: IntervalSet,
+    /// Sections between the lowest and highest covered ones assigned to
+    /// nobody.
+    pub gaps: IntervalSet,
+    /// Most elves assigned to a single section.
+    pub max_load: usize,
+    /// Sections assigned to that many elves.
+    pub busiest: IntervalSet,
+    /// Elves whose every section is also assigned to some other elf. Each
+    /// one could be let go on its own, though not necessarily all of them.
+    pub redundant: Vec<Elf>,
+}
+
 pub struct Data {
-    range_pairs: Vec<((u64, u64), (u64, u64))>,
+    // Sections of each elf, line by line.
+    lines: Vec<Vec<IntervalSet>>,
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let file = std::fs::File::open(input)?;
-        let mut range_pairs = Vec::new();
-        for line_result in std::io::BufReader::new(file).lines() {
+        Self::read(std::io::BufReader::new(file))
+    }
+
+    /// Comma separated ranges of sections, one per elf, each one of `lo-hi`,
+    /// `lo..=hi`, `lo..hi` or a single section.
+    pub fn read<R: BufRead>(reader: R) -> Result<Self> {
+        let mut lines = Vec::new();
+        for (ln, line_result) in (1..).zip(reader.lines()) {
             let line = line_result?;
-            let mut rs = line.split(',');
-            let r1 = rs.next().ok_or_else(|| {
-                anyhow!("missing 1st range in line: {line:?}")
-            })?;
-            let r2 = rs.next().ok_or_else(|| {
-                anyhow!("missing 2nd range in line: {line:?}")
-            })?;
-            let mut r1_bounds = r1.split('-');
-            let mut r2_bounds = r2.split('-');
-            let r1_lo = r1_bounds.next().ok_or_else(|| {
-                anyhow!("missing lower bound in 1st range of line: {line:?}")
-            })?;
-            let r1_hi = r1_bounds.next().ok_or_else(|| {
-                anyhow!("missing upper bound in 1st range of line: {line:?}")
-            })?;
-            let r2_lo = r2_bounds.next().ok_or_else(|| {
-                anyhow!("missing lower bound in 2nd range of line: {line:?}")
-            })?;
-            let r2_hi = r2_bounds.next().ok_or_else(|| {
-                anyhow!("missing upper bound in 2nd range of line: {line:?}")
-            })?;
-            let r1_lo: u64 = r1_lo.parse()?;
-            let r1_hi: u64 = r1_hi.parse()?;
-            let r2_lo: u64 = r2_lo.parse()?;
-            let r2_hi: u64 = r2_hi.parse()?;
-            if r1_lo > r1_hi {
-                return Err(anyhow!(
-                    "invalid range {:?} in line: {line:?}",
-                    (r1_lo, r1_hi)
-                ));
-            }
-            if r2_lo > r2_hi {
-                return Err(anyhow!(
-                    "invalid range {:?} in line: {line:?}",
-                    (r2_lo, r2_hi)
-                ));
-            }
-            let p1 = (r1_lo, r1_hi);
-            let p2 = (r2_lo, r2_hi);
-            let ps = (p1, p2);
-            range_pairs.push(ps);
+            let elves = line
+                .split(',')
+                .map(|s| {
+                    parse_range(s)
+                        .map(IntervalSet::from)
+                        .map_err(|e| anyhow!("{e} in line {ln}: {line:?}"))
+                })
+                .collect::<Result<Vec<IntervalSet>>>()?;
+            lines.push(elves);
         }
-        Ok(Self { range_pairs })
+        Ok(Self { lines })
     }
 
+    /// Lines where one elf's sections contain every other's.
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<usize> {
         let count = self
-            .range_pairs
+            .lines
             .iter()
-            .filter(|(r1, r2)| {
-                is_2nd_range_contained_in_1st(r1, r2)
-                    || is_2nd_range_contained_in_1st(r2, r1)
+            .filter(|elves| {
+                elves
+                    .iter()
+                    .any(|a| elves.iter().all(|b| a.contains_set(b)))
             })
             .count();
         Ok(count)
     }
 
+    /// Lines where some two elves' sections overlap.
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<usize> {
         let count = self
-            .range_pairs
+            .lines
             .iter()
-            .filter(|(r1, r2)| is_ranges_overlap(r1, r2))
+            .filter(|elves| {
+                (0..elves.len()).any(|i| {
+                    elves[i + 1..].iter().any(|b| elves[i].overlaps(b))
+                })
+            })
             .count();
         Ok(count)
     }
-}
 
-fn is_2nd_range_contained_in_1st(
-    (r1_lo, r1_hi): &(u64, u64),
-    (r2_lo, r2_hi): &(u64, u64),
-) -> bool {
-    r2_lo >= r1_lo && r2_hi <= r1_hi
+    /// Each elf with its assigned sections, in order of the input.
+    pub fn elves(&self) -> impl Iterator<Item = (Elf, &IntervalSet)> {
+        (1..).zip(self.lines.iter()).flat_map(|(line, elves)| {
+            (1..)
+                .zip(elves.iter())
+                .map(move |(slot, sections)| (Elf { line, slot }, sections))
+        })
+    }
+
+    /// Coverage of the sections by all the elves together.
+    pub fn coverage(&self) -> Coverage {
+        let counts =
+            interval::sweep(self.elves().map(|(_, sections)| sections));
+        let max_load = counts.iter().map(|(_, n)| *n).max().unwrap_or(0);
+        let covered: IntervalSet = counts
+            .iter()
+            .map(|(sections, _)| sections.clone())
+            .collect();
+        let busiest = counts
+            .iter()
+            .filter(|(_, n)| *n == max_load)
+            .map(|(sections, _)| sections.clone())
+            .collect();
+        let shared: IntervalSet = counts
+            .iter()
+            .filter(|(_, n)| *n > 1)
+            .map(|(sections, _)| sections.clone())
+            .collect();
+        let redundant = self
+            .elves()
+            .filter(|(_, sections)| shared.contains_set(sections))
+            .map(|(elf, _)| elf)
+            .collect();
+        let gaps = match covered.span() {
+            Some(span) => covered.complement(span),
+            None => IntervalSet::new(),
+        };
+        Coverage {
+            covered,
+            gaps,
+            max_load,
+            busiest,
+            redundant,
+        }
+    }
 }
 
-fn is_ranges_overlap(
-    (r1_lo, r1_hi): &(u64, u64),
-    (r2_lo, r2_hi): &(u64, u64),
-) -> bool {
-    r1_hi >= r2_lo && r1_lo <= r2_hi
+fn parse_range(s: &str) -> Result<RangeInclusive<u64>> {
+    let number = |n: &str| {
+        n.parse::<u64>()
+            .map_err(|e| anyhow!("invalid section {n:?} in range {s:?}: {e}"))
+    };
+    let (lo, hi) = if let Some((lo, hi)) = s.split_once("..=") {
+        (number(lo)?, number(hi)?)
+    } else if let Some((lo, hi)) = s.split_once("..") {
+        let hi = number(hi)?
+            .checked_sub(1)
+            .ok_or_else(|| anyhow!("empty range {s:?}"))?;
+        (number(lo)?, hi)
+    } else if let Some((lo, hi)) = s.split_once('-') {
+        (number(lo)?, number(hi)?)
+    } else {
+        (number(s)?, number(s)?)
+    };
+    if lo > hi {
+        return Err(anyhow!("empty range {s:?}"));
+    }
+    Ok(lo..=hi)
 }
 
 #[test]
-fn range_containment() {
-    assert!(is_2nd_range_contained_in_1st(&(1, 5), &(2, 4)));
-    assert!(!is_2nd_range_contained_in_1st(&(1, 5), &(2, 6)));
+fn roster_coverage() {
+    let data = Data::read("2-4,6-8\n2-3,4-5\n12,3-7".as_bytes()).unwrap();
+    let coverage = data.coverage();
+    assert_eq!("2-8,12", coverage.covered.to_string());
+    assert_eq!(8, coverage.covered.len());
+    assert_eq!("9-11", coverage.gaps.to_string());
+    assert_eq!(3, coverage.max_load);
+    assert_eq!("3-4", coverage.busiest.to_string());
+    let redundant: Vec<(usize, usize)> = coverage
+        .redundant
+        .iter()
+        .map(|elf| (elf.line, elf.slot))
+        .collect();
+    assert_eq!(vec![(1, 1), (2, 1), (2, 2), (3, 2)], redundant);
+    let empty = Data::read("".as_bytes()).unwrap();
+    assert_eq!(Coverage::default(), empty.coverage());
 }
 
 #[test]
-fn range_overlap() {
-    assert!(is_ranges_overlap(&(1, 5), &(2, 4)));
-    assert!(is_ranges_overlap(&(1, 5), &(4, 8)));
-    assert!(!is_ranges_overlap(&(1, 5), &(6, 8)));
+fn range_syntaxes() {
+    let sections = |s| parse_range(s).map_err(|e| e.to_string());
+    assert_eq!(Ok(2..=4), sections("2-4"));
+    assert_eq!(Ok(2..=4), sections("2..=4"));
+    assert_eq!(Ok(2..=4), sections("2..5"));
+    assert_eq!(Ok(7..=7), sections("7"));
+    assert_eq!(Err("empty range \"3..3\"".to_string()), sections("3..3"));
+    assert_eq!(Err("empty range \"0..0\"".to_string()), sections("0..0"));
+    assert!(sections("5-3").is_err());
+    assert!(sections("2-4x").is_err());
+    assert!(sections("").is_err());
+    let data =
+        Data::read("1-9,2..4,7\n1,2,3\n1..=5,5-6,9".as_bytes()).unwrap();
+    // Only the 1st line has one elf covering the rest; only the 2nd has no
+    // overlap.
+    assert_eq!(1, data.solve1().unwrap());
+    assert_eq!(2, data.solve2().unwrap());
+    assert_eq!(9, data.elves().count());
+    let err = Data::read("2-4,6-8,".as_bytes()).err().unwrap();
+    assert!(err.to_string().ends_with("in line 1: \"2-4,6-8,\""));
 }
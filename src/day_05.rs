@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 use std::io::BufRead; // lines()
+use std::ops::RangeInclusive;
 
 use anyhow::{anyhow, Result};
 
+use crate::search;
+
 #[derive(Debug, Clone)]
 enum Ix {
     Mv {
@@ -12,117 +15,673 @@ enum Ix {
     },
 }
 
+impl std::fmt::Display for Ix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self::Mv { quant, src, dst } = self;
+        write!(f, "move {quant} from {src} to {dst}")
+    }
+}
+
+/// Crates of each stack, bottom first, by stack id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stacks {
+    // In order of the drawing, each with its id.
+    stacks: Vec<(usize, Vec<String>)>,
+}
+
+impl Stacks {
+    /// Parses a drawing, its last line being the stack ids, given with the
+    /// line numbers for errors. Each crate is a label in brackets, such as
+    /// `[A]` or `[Ab]`, and must be above exactly one of the ids.
+    fn read(lines: &[(usize, String)]) -> Result<Self> {
+        let ((ln, footer), rows) = lines
+            .split_last()
+            .ok_or_else(|| anyhow!("missing drawing of the stacks"))?;
+        let mut stacks: Vec<(usize, Vec<String>)> = Vec::new();
+        // Columns spanned by each id in the footer.
+        let mut columns: Vec<RangeInclusive<usize>> = Vec::new();
+        for (span, token) in tokens(footer, |c| c.is_whitespace()) {
+            let id: usize = token.parse().map_err(|e| {
+                anyhow!("invalid stack id {token:?} in line {ln}: {e}")
+            })?;
+            if stacks.iter().any(|(other, _)| *other == id) {
+                return Err(anyhow!("duplicate stack id {id} in line {ln}"));
+            }
+            stacks.push((id, Vec::new()));
+            columns.push(span);
+        }
+        for (level, (ln, row)) in rows.iter().rev().enumerate() {
+            for (span, token) in tokens(row, |c| c == ' ') {
+                let label = token
+                    .strip_prefix('[')
+                    .and_then(|t| t.strip_suffix(']'))
+                    .filter(|l| !l.is_empty() && !l.contains(['[', ']']))
+                    .ok_or_else(|| {
+                        anyhow!("invalid crate {token:?} in line {ln}")
+                    })?;
+                let mut below =
+                    columns.iter().enumerate().filter(|(_, c)| {
+                        c.start() <= span.end() && span.start() <= c.end()
+                    });
+                let i = match (below.next(), below.next()) {
+                    (Some((i, _)), None) => i,
+                    _ => {
+                        return Err(anyhow!(
+                            "crate {token:?} in line {ln} isn't above \
+                             exactly one stack id"
+                        ))
+                    }
+                };
+                let (id, stack) = &mut stacks[i];
+                if stack.len() != level {
+                    return Err(anyhow!(
+                        "crate {token:?} in line {ln} floats above stack {id}"
+                    ));
+                }
+                stack.push(label.to_string());
+            }
+        }
+        Ok(Self { stacks })
+    }
+
+    pub fn get(&self, id: usize) -> Option<&[String]> {
+        let i = self.position(id)?;
+        Some(&self.stacks[i].1)
+    }
+
+    fn position(&self, id: usize) -> Option<usize> {
+        self.stacks.iter().position(|(other, _)| *other == id)
+    }
+
+    /// Each stack id with its crates, bottom first.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &[String])> {
+        self.stacks.iter().map(|(id, stack)| (*id, &stack[..]))
+    }
+
+    /// Each stack by id with its crates, bottom first, e.g. `1: ZN, 2: M`.
+    fn summary(&self) -> String {
+        self.iter()
+            .map(|(id, stack)| format!("{id}: {}", stack.concat()))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// The labels of the crates on top of each stack, joined together.
+    pub fn msg(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|(_, stack)| stack.last().map_or(" ", String::as_str))
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+}
+
+/// Parts of the line separated by the given characters, with the columns
+/// they span, counting characters rather than bytes.
+fn tokens(
+    line: &str,
+    separator: impl Fn(char) -> bool,
+) -> Vec<(RangeInclusive<usize>, String)> {
+    let mut tokens: Vec<(RangeInclusive<usize>, String)> = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for (col, c) in line.chars().enumerate() {
+        match (separator(c), current.take()) {
+            (true, None) => {}
+            (true, Some((start, token))) => {
+                tokens.push((start..=col - 1, token))
+            }
+            (false, Some((start, mut token))) => {
+                token.push(c);
+                current = Some((start, token));
+            }
+            (false, None) => current = Some((col, c.to_string())),
+        }
+    }
+    if let Some((start, token)) = current {
+        let end = start + token.chars().count() - 1;
+        tokens.push((start..=end, token));
+    }
+    tokens
+}
+
+impl std::str::FromStr for Stacks {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines: Vec<(usize, String)> =
+            (1..).zip(s.lines().map(String::from)).collect();
+        Self::read(&lines)
+    }
+}
+
+/// The puzzle's drawing, each crate as its label in brackets, stacks in
+/// columns as wide as their widest crate or id, a space apart, highest
+/// crates first, then the stack ids below them. Crates and ids are centred
+/// in their columns, so every crate sits above its stack's id.
+impl std::fmt::Display for Stacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths: Vec<usize> = self
+            .stacks
+            .iter()
+            .map(|(id, stack)| {
+                let label = stack.iter().map(|l| l.chars().count()).max();
+                (label.unwrap_or(1) + 2).max(id.to_string().len() + 2)
+            })
+            .collect();
+        let height = self.stacks.iter().map(|(_, s)| s.len()).max();
+        for level in (0..height.unwrap_or(0)).rev() {
+            let row: Vec<String> = self
+                .stacks
+                .iter()
+                .zip(widths.iter())
+                .map(|((_, stack), w)| match stack.get(level) {
+                    Some(label) => format!("{:^w$}", format!("[{label}]")),
+                    None => " ".repeat(*w),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        let footer: Vec<String> = self
+            .stacks
+            .iter()
+            .zip(widths.iter())
+            .map(|((id, _), w)| format!("{id:^w$}"))
+            .collect();
+        writeln!(f, "{}", footer.join(" "))
+    }
+}
+
+/// Crane lifting up to its capacity of crates at once, keeping their order,
+/// so moving more takes several lifts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crane {
+    capacity: usize,
+}
+
+impl Crane {
+    /// One crate at a time.
+    pub const CRATE_MOVER_9000: Self = Self { capacity: 1 };
+    /// Any number of crates at once.
+    pub const CRATE_MOVER_9001: Self = Self {
+        capacity: usize::MAX,
+    };
+
+    pub fn new(capacity: usize) -> Result<Self> {
+        match capacity {
+            0 => Err(anyhow!("invalid crane capacity: 0")),
+            _ => Ok(Self { capacity }),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Carries out the `n`th instruction, see [`Crane::apply`].
+    fn step(&self, stacks: &mut Stacks, n: usize, ix: &Ix) -> Result<usize> {
+        self.apply(stacks, ix).map_err(|e| {
+            anyhow!(
+                "invalid instruction {n} ({ix}): {e}, with stacks {}",
+                stacks.summary()
+            )
+        })
+    }
+
+    /// Moves the crates, returning the number of lifts it took, unless a
+    /// stack doesn't exist or hasn't got enough crates.
+    fn apply(&self, stacks: &mut Stacks, ix: &Ix) -> Result<usize> {
+        let Ix::Mv { quant, src, dst } = *ix;
+        let [i, j] = [src, dst].map(|id| {
+            stacks.position(id).ok_or_else(|| anyhow!("no stack {id}"))
+        });
+        let (i, j) = (i?, j?);
+        let len = stacks.stacks[i].1.len();
+        if len < quant {
+            return Err(anyhow!("stack {src} has only {len} crates"));
+        }
+        let mut lifts = 0;
+        let mut left = quant;
+        while left > 0 {
+            let n = left.min(self.capacity);
+            let len = stacks.stacks[i].1.len();
+            let mut krates = stacks.stacks[i].1.split_off(len - n);
+            stacks.stacks[j].1.append(&mut krates);
+            left -= n;
+            lifts += 1;
+        }
+        Ok(lifts)
+    }
+}
+
+/// Stacks after carrying out the whole plan, and the lifts it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    pub stacks: Stacks,
+    pub lifts: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct Data {
-    stacks: Vec<Vec<char>>,
+    stacks: Stacks,
     instructions: Vec<Ix>,
 }
 
-#[derive(Debug)]
-enum Section {
-    Cts,
-    Ixs,
-}
-
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let file = std::fs::File::open(input)?;
-        let mut section = Section::Cts;
-        let mut stacks: HashMap<usize, Vec<char>> = HashMap::new();
+        Self::read(std::io::BufReader::new(file))
+    }
+
+    /// The drawing of the stacks, see [`Stacks`], a blank line, then the
+    /// plan, one `move N from A to B` per line.
+    pub fn read<R: BufRead>(reader: R) -> Result<Self> {
+        let mut drawing: Vec<(usize, String)> = Vec::new();
         let mut instructions: Vec<Ix> = Vec::new();
-        for line_result in std::io::BufReader::new(file).lines() {
+        let mut lines = (1..).zip(reader.lines());
+        for (ln, line_result) in lines.by_ref() {
             let line = line_result?;
-            match (&section, line.as_str()) {
-                (Section::Cts, "") => section = Section::Ixs,
-                (Section::Cts, _) => {
-                    let mut stack_id = 0;
-                    let mut pos = 0;
-                    loop {
-                        match line.get(pos..pos + 3) {
-                            None => break,
-                            Some(ct) => {
-                                stack_id += 1;
-                                match ct.chars().collect::<Vec<char>>()[..] {
-                                    ['[', crate_name @ 'A'..='Z', ']'] => {
-                                        let mut stack =
-                                            match stacks.get(&stack_id) {
-                                                None => Vec::new(),
-                                                Some(stack) => stack.to_vec(),
-                                            };
-                                        stack.push(crate_name);
-                                        stacks.insert(stack_id, stack);
-                                    },
-                                    [' ', ' ', ' '] => {
-                                    },
-                                    [' ', '0'..='9', ' '] => {
-                                        // XXX We could just ignore this line
-                                        //     with explicit stack_id lables
-                                        //     because the stacks seem to
-                                        //     always be sequential.
-                                    },
-                                    _ => return Err(anyhow!("invalid crate: {ct:?} in line: {line:?}")),
-                                }
-                            }
-                        }
-                        pos += 4;
-                    }
+            if line.is_empty() {
+                break;
+            }
+            drawing.push((ln, line));
+        }
+        let stacks = Stacks::read(&drawing)?;
+        for (ln, line_result) in lines {
+            let line = line_result?;
+            match line.split_whitespace().collect::<Vec<&str>>()[..] {
+                ["move", quant, "from", src, "to", dst] => {
+                    let number = |n: &str| {
+                        n.parse::<usize>().map_err(|e| {
+                            anyhow!("invalid number {n:?} in line {ln}: {e}")
+                        })
+                    };
+                    instructions.push(Ix::Mv {
+                        quant: number(quant)?,
+                        src: number(src)?,
+                        dst: number(dst)?,
+                    });
                 }
-                (Section::Ixs, _) => {
-                    match line.split_whitespace().collect::<Vec<&str>>()[..] {
-                        ["move", quant, "from", src, "to", dst] => {
-                            let quant: usize = quant.parse()?;
-                            let src: usize = src.parse()?;
-                            let dst: usize = dst.parse()?;
-                            instructions.push(Ix::Mv { quant, src, dst });
-                        }
-                        _ => {
-                            return Err(anyhow!(
-                                "invalid instruction line: {line:?}"
-                            ))
-                        }
-                    }
+                _ => {
+                    return Err(anyhow!(
+                        "invalid instruction line {ln}: {line:?}"
+                    ))
                 }
             }
         }
-        let mut stacks_vec = vec![Vec::new(); stacks.len() + 1];
-        for (i, stack) in stacks.iter_mut() {
-            stack.reverse();
-            stacks_vec[*i] = stack.to_vec();
-        }
         Ok(Self {
-            stacks: stacks_vec,
+            stacks,
             instructions,
         })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<String> {
-        let mut stacks = self.stacks.clone();
-        for Ix::Mv { quant, src, dst } in self.instructions.iter() {
-            for _ in 0..*quant {
-                let krate =
-                    stacks[*src].pop().unwrap_or_else(|| unreachable!());
-                stacks[*dst].push(krate);
-            }
-        }
-        Ok(Self::msg(stacks))
+        Ok(self.simulate(Crane::CRATE_MOVER_9000)?.stacks.msg())
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<String> {
+        Ok(self.simulate(Crane::CRATE_MOVER_9001)?.stacks.msg())
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    /// Checks that every instruction of the plan can be carried out, which
+    /// doesn't depend on the crane.
+    pub fn validate(&self) -> Result<()> {
+        self.simulate(Crane::CRATE_MOVER_9001).map(|_| ())
+    }
+
+    /// Carries out the plan with the given crane, up to the first
+    /// instruction which can't be.
+    pub fn simulate(&self, crane: Crane) -> Result<Simulation> {
         let mut stacks = self.stacks.clone();
-        for Ix::Mv { quant, src, dst } in self.instructions.iter() {
-            let len = stacks[*src].len();
-            let mut krates = stacks[*src].split_off(len - quant);
-            stacks[*dst].append(&mut krates);
+        let mut lifts = 0;
+        for (n, ix) in (1..).zip(self.instructions.iter()) {
+            lifts += crane.step(&mut stacks, n, ix)?;
         }
-        Ok(Self::msg(stacks))
+        Ok(Simulation { stacks, lifts })
     }
 
-    fn msg(stacks: Vec<Vec<char>>) -> String {
-        stacks
-            .iter()
-            .map(|stack| stack.last().unwrap_or(&' '))
-            .collect::<String>()
-            .trim()
-            .to_string()
+    /// Shortest plan, in number of instructions, turning the start stacks
+    /// into the target ones with the given crane, found by a breadth-first
+    /// search over every move of every stack's top crates. The target must
+    /// have the same stack ids in the same order and the same crates.
+    ///
+    /// The search gives up once it has generated `max_states` states, about
+    /// a second per million in a release build. Their number grows
+    /// exponentially with the crates: half a dozen crates on three stacks
+    /// take up to a million, eight or nine already tens of millions, and the
+    /// full puzzle input, 56 crates on 9 stacks, is far out of reach.
+    pub fn synthesize(
+        start: Stacks,
+        target: &Stacks,
+        crane: Crane,
+        max_states: usize,
+    ) -> Result<Self> {
+        let ids = |stacks: &Stacks| {
+            stacks.iter().map(|(id, _)| id).collect::<Vec<usize>>()
+        };
+        if ids(&start) != ids(target) {
+            return Err(anyhow!(
+                "unreachable target: stacks {:?} can't become {:?}",
+                ids(&start),
+                ids(target)
+            ));
+        }
+        let crates = |stacks: &Stacks| {
+            let mut crates: Vec<String> = stacks
+                .iter()
+                .flat_map(|(_, stack)| stack.to_vec())
+                .collect();
+            crates.sort();
+            crates
+        };
+        let sorted = crates(&start);
+        if sorted != crates(target) {
+            return Err(anyhow!(
+                "unreachable target: crates {:?} can't become {:?}",
+                sorted.join(" "),
+                crates(target).join(" ")
+            ));
+        }
+        // States are told apart by their crates as numbers, each stack ended
+        // by 0, rather than by their labels.
+        let numbers: HashMap<&str, u32> = (1..)
+            .zip(sorted.iter().map(String::as_str))
+            .map(|(n, l)| (l, n))
+            .collect();
+        let key = |(stacks, _): &(Stacks, Option<Ix>)| {
+            stacks
+                .iter()
+                .flat_map(|(_, stack)| {
+                    stack.iter().map(|l| numbers[l.as_str()]).chain([0])
+                })
+                .collect::<Vec<u32>>()
+        };
+        let mut states = 1;
+        let moves = |(stacks, _): &(Stacks, Option<Ix>)| {
+            let mut next = Vec::new();
+            if states > max_states {
+                return next;
+            }
+            for (src, stack) in stacks.iter() {
+                for (dst, _) in stacks.iter().filter(|(dst, _)| *dst != src) {
+                    for quant in 1..=stack.len() {
+                        let ix = Ix::Mv { quant, src, dst };
+                        let mut stacks = stacks.clone();
+                        if crane.apply(&mut stacks, &ix).is_ok() {
+                            next.push((stacks, Some(ix)));
+                        }
+                    }
+                }
+            }
+            states += next.len();
+            next
+        };
+        let path = search::bfs_by_key(
+            (start.clone(), None),
+            key,
+            moves,
+            |(stacks, _)| stacks == target,
+        );
+        let Some(path) = path else {
+            if states > max_states {
+                return Err(anyhow!(
+                    "no plan found within {max_states} states from {} to {}",
+                    start.summary(),
+                    target.summary()
+                ));
+            }
+            return Err(anyhow!(
+                "unreachable target: no plan turns {} into {}",
+                start.summary(),
+                target.summary()
+            ));
+        };
+        Ok(Self {
+            stacks: start,
+            instructions: path.into_iter().filter_map(|(_, ix)| ix).collect(),
+        })
+    }
+
+    /// Stacks after each instruction of the plan carried out with the given
+    /// crane, ending with the error of the first one which can't be.
+    pub fn steps(
+        &self,
+        crane: Crane,
+    ) -> impl Iterator<Item = Result<Stacks>> + '_ {
+        let mut stacks = Some(self.stacks.clone());
+        (1..)
+            .zip(self.instructions.iter())
+            .map_while(move |(n, ix)| {
+                let state = stacks.as_mut()?;
+                match crane.step(state, n, ix) {
+                    Ok(_) => Some(Ok(state.clone())),
+                    Err(e) => {
+                        stacks = None;
+                        Some(Err(e))
+                    }
+                }
+            })
+    }
+}
+
+/// The whole puzzle input: the drawing, a blank line, then the plan.
+impl std::fmt::Display for Data {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.stacks)?;
+        for ix in self.instructions.iter() {
+            writeln!(f, "{ix}")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn crane_capacity() {
+    let data =
+        Data::load(std::path::Path::new("tests/input/day_05/example.txt"))
+            .unwrap();
+    let sim = |k| data.simulate(Crane::new(k).unwrap()).unwrap();
+    // Moves of 1, 3, 2 and 1 crates.
+    assert_eq!(1 + 3 + 2 + 1, sim(1).lifts);
+    assert_eq!(1 + 2 + 1 + 1, sim(2).lifts);
+    assert_eq!(4, sim(3).lifts);
+    assert_eq!(sim(3), data.simulate(Crane::CRATE_MOVER_9001).unwrap());
+    assert_eq!(sim(1), data.simulate(Crane::CRATE_MOVER_9000).unwrap());
+    assert_eq!("CMZ", sim(1).stacks.msg());
+    assert_eq!("MCD", sim(3).stacks.msg());
+    // Moving 3 by 2 then 1 keeps only the top 2 in order.
+    let labels = ["P", "N", "D", "Z"].map(String::from);
+    assert_eq!(Some(&labels[..]), sim(2).stacks.get(3));
+    assert_eq!(None, sim(2).stacks.get(0));
+    assert_eq!(3, data.stacks().iter().count());
+    assert!(Crane::new(0).is_err());
+}
+
+#[test]
+fn invalid_plans() {
+    let mut data =
+        Data::load(std::path::Path::new("tests/input/day_05/example.txt"))
+            .unwrap();
+    assert!(data.validate().is_ok());
+    let err = |data: &Data| data.validate().unwrap_err().to_string();
+    data.instructions[1] = Ix::Mv {
+        quant: 4,
+        src: 1,
+        dst: 3,
+    };
+    assert_eq!(
+        "invalid instruction 2 (move 4 from 1 to 3): stack 1 has only 3 \
+         crates, with stacks 1: ZND, 2: MC, 3: P",
+        err(&data)
+    );
+    data.instructions[1] = Ix::Mv {
+        quant: 1,
+        src: 1,
+        dst: 4,
+    };
+    assert_eq!(
+        "invalid instruction 2 (move 1 from 1 to 4): no stack 4, with \
+         stacks 1: ZND, 2: MC, 3: P",
+        err(&data)
+    );
+    data.instructions[0] = Ix::Mv {
+        quant: 1,
+        src: 0,
+        dst: 1,
+    };
+    assert!(err(&data).starts_with("invalid instruction 1 (move 1 from 0"));
+    assert!(data.solve1().is_err() && data.solve2().is_err());
+}
+
+#[test]
+fn drawing_round_trip() {
+    for kind in ["example", "input"] {
+        let path = format!("tests/input/day_05/{kind}.txt");
+        let data = Data::load(std::path::Path::new(&path)).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text, data.to_string());
+    }
+    let data =
+        Data::load(std::path::Path::new("tests/input/day_05/example.txt"))
+            .unwrap();
+    let frames: Vec<String> = data
+        .steps(Crane::CRATE_MOVER_9000)
+        .map(|stacks| stacks.unwrap().to_string())
+        .collect();
+    assert_eq!(4, frames.len());
+    assert_eq!(
+        "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n",
+        frames[0]
+    );
+    assert_eq!(
+        "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3 \n",
+        frames[1]
+    );
+    assert_eq!(
+        data.simulate(Crane::CRATE_MOVER_9000)
+            .unwrap()
+            .stacks
+            .to_string(),
+        frames[3]
+    );
+}
+
+#[test]
+fn crate_labels() {
+    let drawing = "\
+[Ab]             
+[C]       [Def]  
+ 3    10   12    ";
+    let stacks: Stacks = drawing.parse().unwrap();
+    assert_eq!(
+        vec![3, 10, 12],
+        stacks.iter().map(|(id, _)| id).collect::<Vec<usize>>()
+    );
+    assert_eq!(Some(&["C", "Ab"].map(String::from)[..]), stacks.get(3));
+    assert_eq!(Some(&[][..]), stacks.get(10));
+    assert_eq!(None, stacks.get(1));
+    assert_eq!("Ab Def", stacks.msg());
+    let rendered = stacks.to_string();
+    assert_eq!(
+        "[Ab]           \n[C]       [Def]\n 3    10   12  \n",
+        rendered
+    );
+    assert_eq!(stacks, rendered.parse().unwrap());
+    let mixed: Stacks =
+        "  [C]      \n[Abcde] [X]\n   1     2 ".parse().unwrap();
+    let rendered = mixed.to_string();
+    assert_eq!("  [C]      \n[Abcde] [X]\n   1     2 \n", rendered);
+    assert_eq!(mixed, rendered.parse().unwrap());
+    let input =
+        format!("{drawing}\n\nmove 2 from 3 to 10\nmove 1 from 10 to 12\n");
+    let data = Data::read(input.as_bytes()).unwrap();
+    assert_eq!("AbC", data.solve1().unwrap());
+    assert_eq!("CAb", data.solve2().unwrap());
+    let err = |s: &str| s.parse::<Stacks>().unwrap_err().to_string();
+    assert_eq!("invalid crate \"[A\" in line 1", err("[A\n 1 "));
+    assert_eq!("duplicate stack id 1 in line 2", err("[A]\n 1   1 "));
+    assert!(err("[A]   [B]\n 1 ").contains("isn't above exactly one"));
+    assert!(err("[A]    \n    [B]\n 1   2 ").contains("floats above stack 1"));
+    assert!(err(" 1   x ").starts_with("invalid stack id \"x\" in line 1"));
+}
+
+#[test]
+fn plan_synthesis() {
+    let data =
+        Data::load(std::path::Path::new("tests/input/day_05/example.txt"))
+            .unwrap();
+    for crane in [Crane::CRATE_MOVER_9000, Crane::CRATE_MOVER_9001] {
+        let target = data.simulate(crane).unwrap().stacks;
+        let plan =
+            Data::synthesize(data.stacks().clone(), &target, crane, 100_000)
+                .unwrap();
+        assert!(plan.instructions.len() <= data.instructions.len());
+        let reread = Data::read(plan.to_string().as_bytes()).unwrap();
+        assert_eq!(target, reread.simulate(crane).unwrap().stacks);
     }
+    let start: Stacks = "[A]    \n[B] [C]\n 1   2 ".parse().unwrap();
+    let target: Stacks = "    [A]\n[C] [B]\n 1   2 ".parse().unwrap();
+    let plan = |crane| {
+        Data::synthesize(start.clone(), &target, crane, 100_000)
+            .map(|data| {
+                data.instructions
+                    .iter()
+                    .map(Ix::to_string)
+                    .collect::<Vec<String>>()
+            })
+            .map_err(|e| e.to_string())
+    };
+    let moves = [
+        "move 2 from 1 to 2",
+        "move 3 from 2 to 1",
+        "move 2 from 1 to 2",
+    ];
+    assert_eq!(
+        Ok(moves.map(String::from).to_vec()),
+        plan(Crane::CRATE_MOVER_9001)
+    );
+    // One crate at a time, two stacks can't swap their bottom crates.
+    assert_eq!(
+        Err(
+            "unreachable target: no plan turns 1: BA, 2: C into 1: C, 2: BA"
+                .to_string()
+        ),
+        plan(Crane::CRATE_MOVER_9000)
+    );
+    let same =
+        Data::synthesize(start.clone(), &start, Crane::CRATE_MOVER_9000, 1)
+            .unwrap();
+    assert!(same.instructions.is_empty());
+    let other: Stacks = "[A] [D]\n 1   2 ".parse().unwrap();
+    assert_eq!(
+        Err("unreachable target: crates \"A B C\" can't become \"A D\""
+            .to_string()),
+        Data::synthesize(start.clone(), &other, Crane::CRATE_MOVER_9000, 1)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    );
+    let renamed: Stacks = "[A]    \n[B] [C]\n 1   3 ".parse().unwrap();
+    assert!(
+        Data::synthesize(start, &renamed, Crane::CRATE_MOVER_9000, 1)
+            .is_err()
+    );
+    // Far too many arrangements of the full input to search them all.
+    let data =
+        Data::load(std::path::Path::new("tests/input/day_05/input.txt"))
+            .unwrap();
+    let crane = Crane::CRATE_MOVER_9001;
+    let target = data.simulate(crane).unwrap().stacks;
+    let err = Data::synthesize(data.stacks().clone(), &target, crane, 10_000)
+        .err()
+        .unwrap();
+    assert!(err
+        .to_string()
+        .starts_with("no plan found within 10000 states"));
 }
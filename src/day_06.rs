@@ -7,15 +7,18 @@ pub struct Data {
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let signal = std::fs::read_to_string(input)?.trim().to_string();
         Ok(Self { signal })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<usize> {
         marker(&self.signal, 4).ok_or_else(|| anyhow!("no solution found"))
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<usize> {
         marker(&self.signal, 14).ok_or_else(|| anyhow!("no solution found"))
     }
@@ -26,8 +29,8 @@ impl Data {
 // > together and use popcount (which is a single instruction on most
 // > processors and there's a compiler intrinsic)
 fn marker(s: &str, l: usize) -> Option<usize> {
-    for (i, _) in s.chars().enumerate() {
-        let n = i + l;
+    for n in l..=s.len() {
+        let i = n - l;
         if s[i..n].chars().collect::<HashSet<char>>().len() == l {
             return Some(n);
         }
@@ -21,6 +21,7 @@ pub struct Data {
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let file = std::fs::File::open(input)?;
         let mut commands = Vec::new();
@@ -65,16 +66,13 @@ impl Data {
         Ok(Self { commands })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<u64> {
-        let sum = self
-            .dir_sizes()
-            .iter()
-            .map(|(_, s)| s)
-            .filter(|s| **s <= 100000)
-            .sum();
+        let sum = self.dir_sizes().values().filter(|s| **s <= 100000).sum();
         Ok(sum)
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<Option<u64>> {
         let total = 70000000;
         let need = 30000000;
@@ -85,11 +83,8 @@ impl Data {
         let available = total - used;
         if need > available {
             let missing = need - available;
-            let smallest_to_fill_missing = sizes
-                .iter()
-                .map(|(_, s)| *s)
-                .filter(|s| *s >= missing)
-                .min();
+            let smallest_to_fill_missing =
+                sizes.values().copied().filter(|s| *s >= missing).min();
             Ok(smallest_to_fill_missing)
         } else {
             Ok(None)
@@ -132,6 +127,9 @@ impl Data {
                 }
             }
         }
+        for (path, size) in sizes.iter() {
+            tracing::debug!(?path, size);
+        }
         sizes
     }
 }
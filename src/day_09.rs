@@ -3,6 +3,8 @@ use std::io::BufRead;
 
 use anyhow::{anyhow, Result};
 
+use crate::geom::Point2;
+
 #[derive(Clone, Copy)]
 enum Dir {
     R,
@@ -33,6 +35,7 @@ pub struct Data {
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let file = std::fs::File::open(input)?;
         let mut moves = Vec::new();
@@ -54,24 +57,26 @@ impl Data {
         Ok(Self { moves })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<usize> {
         Ok(solve(&self.moves, 2))
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<usize> {
         Ok(solve(&self.moves, 10))
     }
 }
 
 fn solve(moves: &[Mv], n: usize) -> usize {
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    let mut knot_pos = vec![(4, 0); n];
+    let mut visited: HashSet<Point2> = HashSet::new();
+    let mut knot_pos = vec![Point2::new(0, 4); n];
     let head = 0;
     let tail = n - 1;
     visited.insert(knot_pos[tail]);
     for d in deltas(moves) {
         // head
-        knot_pos[head] = add(knot_pos[head], d);
+        knot_pos[head] += d;
 
         // tail
         for current in 1..n {
@@ -79,46 +84,25 @@ fn solve(moves: &[Mv], n: usize) -> usize {
             knot_pos[current] = catchup(knot_pos[current], knot_pos[ahead]);
             visited.insert(knot_pos[tail]);
         }
+        tracing::debug!(rope = ?knot_pos);
     }
     visited.len()
 }
 
-type Pos = (i32, i32);
-
-fn catchup(current: Pos, ahead: Pos) -> Pos {
-    match distance(ahead, current) {
+fn catchup(current: Point2, ahead: Point2) -> Point2 {
+    match ahead.chebyshev(current) {
         0 | 1 => current,
-        _ => add(current, sig(sub(ahead, current))),
+        _ => current + (ahead - current).signum(),
     }
 }
 
-fn add((r1, k1): Pos, (r2, k2): Pos) -> Pos {
-    ((r1 + r2), (k1 + k2))
-}
-
-fn sub((r1, k1): Pos, (r2, k2): Pos) -> Pos {
-    ((r1 - r2), (k1 - k2))
-}
-
-fn sig((r, k): Pos) -> Pos {
-    (r.signum(), k.signum())
-}
-
-fn distance(p1: Pos, p2: Pos) -> u32 {
-    let (r1, k1) = p1;
-    let (r2, k2) = p2;
-    let rd = r1.abs_diff(r2);
-    let kd = k1.abs_diff(k2);
-    std::cmp::max(rd, kd)
-}
-
-fn deltas(moves: &[Mv]) -> impl Iterator<Item = Pos> + '_ {
+fn deltas(moves: &[Mv]) -> impl Iterator<Item = Point2> + '_ {
     moves.iter().flat_map(|(dir, delta): &Mv| {
         (0..(*delta)).map(move |_| match dir {
-            Dir::R => (0, 1),
-            Dir::L => (0, -1),
-            Dir::D => (1, 0),
-            Dir::U => (-1, 0),
+            Dir::R => Point2::new(1, 0),
+            Dir::L => Point2::new(-1, 0),
+            Dir::D => Point2::new(0, 1),
+            Dir::U => Point2::new(0, -1),
         })
     })
 }
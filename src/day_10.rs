@@ -28,12 +28,11 @@ pub struct Data {
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let file = std::fs::File::open(input)?;
         let mut program = Vec::new();
-        for (_ln, line_result) in
-            std::io::BufReader::new(file).lines().enumerate()
-        {
+        for line_result in std::io::BufReader::new(file).lines() {
             let line = line_result?;
             match line.split_whitespace().collect::<Vec<&str>>()[..] {
                 ["noop"] => program.push(Ix::Noop),
@@ -47,6 +46,7 @@ impl Data {
         Ok(Self { program })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<i32> {
         let mut x = 1;
         let mut strengths = 0;
@@ -61,6 +61,7 @@ impl Data {
         Ok(strengths)
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<String> {
         let mut buf = String::new();
         let mut sprite_pos: i32 = 1;
@@ -79,7 +80,7 @@ impl Data {
             // after cycle
             sprite_pos += sprite_pos_delta;
         }
-        eprint!("{}", buf); // For visual reading.
+        tracing::debug!("CRT:\n{}", buf); // For visual reading.
         Ok(buf)
     }
 }
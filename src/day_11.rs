@@ -86,6 +86,7 @@ impl MonkeyTmp {
 }
 
 impl Data {
+    #[tracing::instrument]
     pub fn load(input: &std::path::Path) -> Result<Self> {
         let file = std::fs::File::open(input)?;
         let mut monkeys = Vec::new();
@@ -173,12 +174,14 @@ impl Data {
         Ok(Self { monkeys })
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve1(&self) -> Result<u64> {
         let rounds = 20;
-        let reduce = &mut |w| w / 3;
-        Ok(monkey_biz_level(&self.monkeys, rounds, reduce))
+        let reduce = |w| w / 3;
+        Ok(monkey_biz_level(&self.monkeys, rounds, &reduce))
     }
 
+    #[tracing::instrument(skip(self))]
     pub fn solve2(&self) -> Result<u64> {
         let modulus: u64 = self
             .monkeys
@@ -186,40 +189,86 @@ impl Data {
             .map(|Monkey { test, .. }| test)
             .product();
         let rounds = 10_000;
-        let reduce = &mut |w| w % modulus;
-        Ok(monkey_biz_level(&self.monkeys, rounds, reduce))
+        let reduce = |w| w % modulus;
+        Ok(monkey_biz_level(&self.monkeys, rounds, &reduce))
     }
 }
 
 fn monkey_biz_level(
     monkeys: &[Monkey],
     rounds: usize,
-
-    // Can we not somehow have all these muts for such a simple closure?
-    // Which doesn't _actually_ mutate anything.
-    reduce: &mut dyn FnMut(u64) -> u64,
+    reduce: &(dyn Fn(u64) -> u64 + Sync),
 ) -> u64 {
-    let mut monkeys = monkeys.to_vec();
-    let mut count = vec![0; monkeys.len()];
-    for _ in 0..rounds {
-        for m in 0..monkeys.len() {
-            assert_eq!(m, monkeys[m].id);
-            let items: Vec<u64> = monkeys[m].items.clone();
-            monkeys[m].items = Vec::new();
-            for w0 in items {
-                count[m] += 1;
-                let w1 = eval(monkeys[m].operation, w0);
-                let w2 = reduce(w1);
-                let dst = if w2 % monkeys[m].test == 0 {
-                    monkeys[m].dst_if_true
-                } else {
-                    monkeys[m].dst_if_false
-                };
-                monkeys[dst].items.push(w2);
-            }
+    for (m, monkey) in monkeys.iter().enumerate() {
+        assert_eq!(m, monkey.id);
+    }
+    // An item's path does not depend on any other item, so each one can be
+    // followed through all the rounds on its own and the counts summed up.
+    let items: Vec<(usize, u64)> = monkeys
+        .iter()
+        .flat_map(|Monkey { id, items, .. }| items.iter().map(|w| (*id, *w)))
+        .collect();
+    let n = monkeys.len();
+    // Counts are only kept per round when they're going to be logged.
+    let per_round = tracing::enabled!(tracing::Level::DEBUG);
+    let slots = if per_round { rounds } else { 1 };
+    let zero = || vec![0; slots * n];
+    let follow = |mut count: Vec<u64>, item| {
+        inspect(monkeys, rounds, reduce, item, &mut count);
+        count
+    };
+    let sum = |mut c1: Vec<u64>, c2: Vec<u64>| {
+        c1.iter_mut().zip(c2).for_each(|(n1, n2)| *n1 += n2);
+        c1
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let mut count = items.into_iter().fold(zero(), follow);
+
+    #[cfg(feature = "parallel")]
+    let mut count = {
+        use rayon::prelude::*;
+        items.into_par_iter().fold(zero, follow).reduce(zero, sum)
+    };
+
+    if per_round {
+        let per_round = count;
+        count = vec![0; n];
+        for (round, round_count) in per_round.chunks(n).enumerate() {
+            count = sum(count, round_count.to_vec());
+            tracing::debug!(round = round + 1, inspections = ?count);
         }
     }
     count.sort();
     count.reverse();
     count[..2].iter().product()
 }
+
+/// Counts each inspection of the given item per monkey, and per round too
+/// if there's room for every round's counts.
+fn inspect(
+    monkeys: &[Monkey],
+    rounds: usize,
+    reduce: &(dyn Fn(u64) -> u64 + Sync),
+    (mut m, mut w): (usize, u64),
+    count: &mut [u64],
+) {
+    let n = monkeys.len();
+    let per_round = count.len() > n;
+    let mut round = 0;
+    while round < rounds {
+        count[if per_round { round * n + m } else { m }] += 1;
+        w = reduce(eval(monkeys[m].operation, w));
+        let dst = if w.is_multiple_of(monkeys[m].test) {
+            monkeys[m].dst_if_true
+        } else {
+            monkeys[m].dst_if_false
+        };
+        // Monkeys take turns in order of id, so an item thrown back to an
+        // earlier (or the same) monkey waits for that monkey's next turn.
+        if dst <= m {
+            round += 1;
+        }
+        m = dst;
+    }
+}
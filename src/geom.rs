@@ -0,0 +1,235 @@
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// Component-wise operations shared by all point types.
+pub trait Coords: Copy {
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn splat(n: i32) -> Self;
+
+    /// Whether every component is less than or equal to the other's.
+    fn all_le(self, other: Self) -> bool;
+}
+
+macro_rules! point {
+    ($name:ident { $($c:ident),+ }) => {
+        #[derive(
+            Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+        )]
+        pub struct $name {
+            $(pub $c: i32),+
+        }
+
+        impl $name {
+            pub const ZERO: Self = Self { $($c: 0),+ };
+
+            pub const fn new($($c: i32),+) -> Self {
+                Self { $($c),+ }
+            }
+
+            pub fn signum(self) -> Self {
+                Self { $($c: self.$c.signum()),+ }
+            }
+
+            /// Moves along any axes at once, i.e. king moves.
+            pub fn chebyshev(self, other: Self) -> u32 {
+                0 $(.max(self.$c.abs_diff(other.$c)))+
+            }
+
+            /// Moves along one axis at a time, i.e. taxicab moves.
+            pub fn manhattan(self, other: Self) -> u32 {
+                0 $(+ self.$c.abs_diff(other.$c))+
+            }
+        }
+
+        impl Coords for $name {
+            fn min(self, other: Self) -> Self {
+                Self { $($c: self.$c.min(other.$c)),+ }
+            }
+
+            fn max(self, other: Self) -> Self {
+                Self { $($c: self.$c.max(other.$c)),+ }
+            }
+
+            fn splat(n: i32) -> Self {
+                Self { $($c: n),+ }
+            }
+
+            fn all_le(self, other: Self) -> bool {
+                true $(&& self.$c <= other.$c)+
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self { $($c: self.$c + other.$c),+ }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self { $($c: self.$c - other.$c),+ }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($c: -self.$c),+ }
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+point!(Point2 { x, y });
+point!(Point3 { x, y, z });
+
+impl Point2 {
+    /// Unit offsets along each axis, in both directions.
+    pub const ORTHOGONAL: [Self; 4] = [
+        Self::new(0, -1),
+        Self::new(0, 1),
+        Self::new(-1, 0),
+        Self::new(1, 0),
+    ];
+
+    pub fn neighbours4(self) -> impl Iterator<Item = Self> {
+        Self::ORTHOGONAL
+            .into_iter()
+            .map(move |offset| self + offset)
+    }
+
+    /// Orthogonal and diagonal neighbours, row by row.
+    pub fn neighbours8(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Self::new(x, y)))
+            .filter(|offset| *offset != Self::ZERO)
+            .map(move |offset| self + offset)
+    }
+}
+
+impl Point3 {
+    /// Unit offsets along each axis, in both directions.
+    pub const ORTHOGONAL: [Self; 6] = [
+        Self::new(1, 0, 0),
+        Self::new(0, 1, 0),
+        Self::new(0, 0, 1),
+        Self::new(-1, 0, 0),
+        Self::new(0, -1, 0),
+        Self::new(0, 0, -1),
+    ];
+
+    /// Points sharing a face with this one.
+    pub fn neighbours6(self) -> impl Iterator<Item = Self> {
+        Self::ORTHOGONAL
+            .into_iter()
+            .map(move |offset| self + offset)
+    }
+
+    /// Points sharing a face, an edge or a corner with this one.
+    pub fn neighbours26(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|z| (-1..=1).map(move |y| (y, z)))
+            .flat_map(|(y, z)| (-1..=1).map(move |x| Self::new(x, y, z)))
+            .filter(|offset| *offset != Self::ZERO)
+            .map(move |offset| self + offset)
+    }
+}
+
+/// Axis-aligned bounding box, inclusive of both corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds<P> {
+    pub lo: P,
+    pub hi: P,
+}
+
+impl<P: Coords> Bounds<P> {
+    pub fn new(p: P) -> Self {
+        Self { lo: p, hi: p }
+    }
+
+    /// Smallest box containing all the points, if there are any.
+    pub fn from_points(points: impl IntoIterator<Item = P>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = Self::new(points.next()?);
+        Some(points.fold(first, Self::expand))
+    }
+
+    pub fn contains(&self, p: P) -> bool {
+        self.lo.all_le(p) && p.all_le(self.hi)
+    }
+
+    /// Smallest box containing this one and the given point.
+    pub fn expand(self, p: P) -> Self {
+        Self {
+            lo: self.lo.min(p),
+            hi: self.hi.max(p),
+        }
+    }
+
+    /// Adds a margin of the given width on every side.
+    pub fn grow(self, n: i32) -> Self
+    where
+        P: Add<Output = P> + Sub<Output = P>,
+    {
+        Self {
+            lo: self.lo - P::splat(n),
+            hi: self.hi + P::splat(n),
+        }
+    }
+}
+
+#[test]
+fn point_arithmetic() {
+    let a = Point2::new(3, -4);
+    let b = Point2::new(1, 2);
+    assert_eq!(Point2::new(4, -2), a + b);
+    assert_eq!(Point2::new(2, -6), a - b);
+    assert_eq!(Point2::new(-3, 4), -a);
+    assert_eq!(Point2::new(1, -1), a.signum());
+    assert_eq!(6, a.chebyshev(b));
+    assert_eq!(8, a.manhattan(b));
+    assert_eq!(3, Point3::new(1, 2, 3).chebyshev(Point3::ZERO));
+    assert_eq!(6, Point3::new(1, -2, 3).manhattan(Point3::ZERO));
+}
+
+#[test]
+fn point_neighbours() {
+    let p = Point3::new(1, 1, 1);
+    assert_eq!(6, p.neighbours6().count());
+    assert_eq!(26, p.neighbours26().count());
+    assert!(p.neighbours26().all(|n| n.chebyshev(p) == 1));
+    assert!(p.neighbours6().all(|n| n.manhattan(p) == 1));
+    let p = Point2::ZERO;
+    assert_eq!(4, p.neighbours4().count());
+    assert!(p.neighbours8().all(|n| n.chebyshev(p) == 1));
+}
+
+#[test]
+fn bounds() {
+    let points = [Point2::new(1, 5), Point2::new(-2, 3), Point2::new(0, 7)];
+    let b = Bounds::from_points(points).unwrap();
+    assert_eq!(Point2::new(-2, 3), b.lo);
+    assert_eq!(Point2::new(1, 7), b.hi);
+    assert!(points.iter().all(|p| b.contains(*p)));
+    assert!(!b.contains(Point2::new(2, 5)));
+    assert!(b.grow(1).contains(Point2::new(2, 8)));
+    assert!(b.expand(Point2::new(5, 0)).contains(Point2::new(4, 1)));
+    assert_eq!(None, Bounds::<Point3>::from_points([]));
+}
@@ -0,0 +1,289 @@
+use std::io::BufRead;
+
+use anyhow::{anyhow, Result};
+
+use crate::geom::Point2;
+
+/// Column as x and row as y, both counting from 0 in the top left corner.
+pub type Point = Point2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    pub const ALL: [Self; 4] =
+        [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    pub fn offset(&self) -> Point {
+        match self {
+            Self::Up => Point::new(0, -1),
+            Self::Down => Point::new(0, 1),
+            Self::Left => Point::new(-1, 0),
+            Self::Right => Point::new(1, 0),
+        }
+    }
+}
+
+/// Rectangular 2D grid, stored row-major.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    nrows: usize,
+    ncols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let nrows = rows.len();
+        let ncols = rows.first().map_or(0, |row| row.len());
+        let mut cells = Vec::with_capacity(nrows * ncols);
+        for (r, row) in rows.into_iter().enumerate() {
+            if row.len() != ncols {
+                return Err(anyhow!(
+                    "non-rectangular grid: row {} has {} cells instead of {}",
+                    r + 1,
+                    row.len(),
+                    ncols
+                ));
+            }
+            cells.extend(row);
+        }
+        Ok(Self {
+            cells,
+            nrows,
+            ncols,
+        })
+    }
+
+    /// Parses a character map, one row per line, each character mapped to a
+    /// cell by the given function.
+    pub fn read<R: BufRead>(
+        reader: R,
+        mut cell: impl FnMut(char) -> Result<T>,
+    ) -> Result<Self> {
+        let mut rows = Vec::new();
+        for (ln, line_result) in
+            reader.lines().enumerate().map(|(i, l)| (i + 1, l))
+        {
+            let line = line_result?;
+            let mut row = Vec::with_capacity(line.len());
+            for (col, c) in line.chars().enumerate().map(|(i, c)| (i + 1, c))
+            {
+                let x = cell(c).map_err(|e| {
+                    anyhow!(
+                        "invalid cell {c:?} in line {ln}, column {col}: {e}"
+                    )
+                })?;
+                row.push(x);
+            }
+            rows.push(row);
+        }
+        Self::from_rows(rows)
+    }
+
+    pub fn parse(
+        s: &str,
+        cell: impl FnMut(char) -> Result<T>,
+    ) -> Result<Self> {
+        Self::read(s.as_bytes(), cell)
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.index_of(p).is_some()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index_of(p).map(|i| &mut self.cells[i])
+    }
+
+    fn index_of(&self, Point { x, y }: Point) -> Option<usize> {
+        let r = usize::try_from(y).ok().filter(|r| *r < self.nrows)?;
+        let k = usize::try_from(x).ok().filter(|k| *k < self.ncols)?;
+        Some(r * self.ncols + k)
+    }
+
+    /// All points, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let ncols = self.ncols;
+        (0..self.nrows).flat_map(move |r| {
+            (0..ncols).map(move |k| Point::new(k as i32, r as i32))
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, r: usize) -> impl Iterator<Item = &T> {
+        self.cells
+            .chunks(self.ncols.max(1))
+            .nth(r)
+            .into_iter()
+            .flatten()
+    }
+
+    pub fn col(&self, k: usize) -> impl Iterator<Item = &T> {
+        let nrows = if k < self.ncols { self.nrows } else { 0 };
+        (0..nrows).map(move |r| &self.cells[r * self.ncols + k])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.nrows).map(|r| self.row(r))
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.ncols).map(|k| self.col(k))
+    }
+
+    /// Adjacent point in the given direction, if within the grid.
+    pub fn step(&self, p: Point, dir: Dir) -> Option<Point> {
+        let p = p + dir.offset();
+        self.contains(p).then_some(p)
+    }
+
+    /// Points from the given one (excluded) to the edge of the grid.
+    pub fn ray(
+        &self,
+        p: Point,
+        dir: Dir,
+    ) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(self.step(p, dir), move |q| self.step(*q, dir))
+    }
+
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Dir::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(p, dir))
+    }
+
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours8().filter(|n| self.contains(*n))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            nrows: self.nrows,
+            ncols: self.ncols,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(nrows: usize, ncols: usize, x: T) -> Self {
+        Self {
+            cells: vec![x; nrows * ncols],
+            nrows,
+            ncols,
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self {
+            cells: self.cols().flatten().cloned().collect(),
+            nrows: self.ncols,
+            ncols: self.nrows,
+        }
+    }
+}
+
+impl<T> std::ops::Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).unwrap_or_else(|| {
+            panic!("point {p:?} out of {}x{} grid", self.nrows, self.ncols)
+        })
+    }
+}
+
+impl<T> std::ops::IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        let (nrows, ncols) = (self.nrows, self.ncols);
+        self.get_mut(p).unwrap_or_else(|| {
+            panic!("point {p:?} out of {nrows}x{ncols} grid")
+        })
+    }
+}
+
+/// Each cell rendered as-is, each row on its own line.
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for x in row {
+                write!(f, "{x}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn digits(s: &str) -> Result<Grid<u32>> {
+    Grid::parse(s, |c| c.to_digit(10).ok_or_else(|| anyhow!("not a digit")))
+}
+
+#[test]
+fn grid_parse() {
+    let g = digits("123\n456\n").unwrap();
+    assert_eq!((2, 3), (g.nrows(), g.ncols()));
+    assert_eq!(Some(&6), g.get(Point::new(2, 1)));
+    assert_eq!(None, g.get(Point::new(0, 2)));
+    assert_eq!(None, g.get(Point::new(3, 0)));
+    assert_eq!(None, g.get(Point::new(-1, 0)));
+    assert_eq!("123\n456\n", g.to_string());
+    assert!(digits("123\n45\n").is_err());
+    assert!(digits("123\n4x6\n").is_err());
+}
+
+#[test]
+fn grid_neighbours() {
+    let g = digits("123\n456\n789").unwrap();
+    let n4 = |p| g.neighbours4(p).map(|p| g[p]).collect::<Vec<u32>>();
+    let n8 = |p| g.neighbours8(p).map(|p| g[p]).collect::<Vec<u32>>();
+    assert_eq!(vec![4, 2], n4(Point::new(0, 0)));
+    assert_eq!(vec![2, 8, 4, 6], n4(Point::new(1, 1)));
+    assert_eq!(vec![2, 4, 5], n8(Point::new(0, 0)));
+    assert_eq!(vec![1, 2, 3, 4, 6, 7, 8, 9], n8(Point::new(1, 1)));
+}
+
+#[test]
+fn grid_rays() {
+    let g = digits("123\n456\n789").unwrap();
+    let ray = |p, d| g.ray(p, d).map(|p| g[p]).collect::<Vec<u32>>();
+    assert_eq!(vec![4, 1], ray(Point::new(0, 2), Dir::Up));
+    assert_eq!(vec![8, 9], ray(Point::new(0, 2), Dir::Right));
+    assert_eq!(Vec::<u32>::new(), ray(Point::new(0, 2), Dir::Down));
+    assert_eq!(vec![5, 4], ray(Point::new(2, 1), Dir::Left));
+}
+
+#[test]
+fn grid_transpose() {
+    let g = digits("123\n456").unwrap();
+    assert_eq!("14\n25\n36\n", g.transpose().to_string());
+    assert_eq!(g, g.transpose().transpose());
+    assert_eq!(vec![2, 5], g.col(1).copied().collect::<Vec<u32>>());
+    assert_eq!(vec![4, 5, 6], g.row(1).copied().collect::<Vec<u32>>());
+}
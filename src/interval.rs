@@ -0,0 +1,300 @@
+//! Sets of integers, kept as sorted, disjoint and non-adjacent inclusive
+//! ranges, so that a set of long runs stays small.
+
+use std::ops::RangeInclusive;
+
+/// Inclusive bounds.
+type Range = (u64, u64);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given range, merging it with any it overlaps or touches.
+    pub fn insert(&mut self, range: RangeInclusive<u64>) {
+        let (mut lo, mut hi) = range.into_inner();
+        if lo > hi {
+            return;
+        }
+        // Ranges before the new one, not touching it.
+        let start = self.ranges.partition_point(|(_, h)| {
+            h.checked_add(1).is_some_and(|h| h < lo)
+        });
+        // Ranges overlapping or touching the new one.
+        let end = start
+            + self.ranges[start..]
+                .iter()
+                .take_while(|(l, _)| {
+                    hi.checked_add(1).is_none_or(|h| *l <= h)
+                })
+                .count();
+        if start < end {
+            lo = lo.min(self.ranges[start].0);
+            hi = hi.max(self.ranges[end - 1].1);
+        }
+        self.ranges.splice(start..end, [(lo, hi)]);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut set = self.clone();
+        for range in other.ranges() {
+            set.insert(range);
+        }
+        set
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            if is_ranges_overlap(a, b) {
+                ranges.push((a.0.max(b.0), a.1.min(b.1)));
+            }
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Numbers in this set but not in the other.
+    pub fn difference(&self, other: &Self) -> Self {
+        match self.span() {
+            Some(span) => self.intersection(&other.complement(span)),
+            None => Self::new(),
+        }
+    }
+
+    /// Numbers within the given bounds but not in this set.
+    pub fn complement(&self, bounds: RangeInclusive<u64>) -> Self {
+        let (lo, hi) = bounds.into_inner();
+        let mut set = Self::new();
+        // Lowest number not yet accounted for, none past `u64::MAX`.
+        let mut next = Some(lo);
+        for (l, h) in self.ranges.iter().copied() {
+            let Some(n) = next else { break };
+            if l > hi {
+                break;
+            }
+            if h < n {
+                continue;
+            }
+            if n < l {
+                set.insert(n..=l - 1);
+            }
+            next = h.checked_add(1);
+        }
+        if let Some(n) = next {
+            set.insert(n..=hi);
+        }
+        set
+    }
+
+    /// Number of numbers in the set, saturating at `u64::MAX`.
+    pub fn len(&self) -> u64 {
+        self.ranges
+            .iter()
+            .map(|(lo, hi)| (hi - lo).saturating_add(1))
+            .fold(0, u64::saturating_add)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, n: u64) -> bool {
+        let i = self.ranges.partition_point(|(_, hi)| *hi < n);
+        self.ranges.get(i).is_some_and(|(lo, _)| *lo <= n)
+    }
+
+    /// Whether every number of the other set is in this one.
+    pub fn contains_set(&self, other: &Self) -> bool {
+        other.ranges.iter().all(|b| {
+            let i = self.ranges.partition_point(|(_, hi)| *hi < b.0);
+            self.ranges
+                .get(i)
+                .is_some_and(|a| is_2nd_range_contained_in_1st(a, b))
+        })
+    }
+
+    /// Whether any number is in both sets.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// Smallest range containing the whole set, unless it's empty.
+    pub fn span(&self) -> Option<RangeInclusive<u64>> {
+        let (lo, _) = self.ranges.first()?;
+        let (_, hi) = self.ranges.last()?;
+        Some(*lo..=*hi)
+    }
+
+    /// The disjoint ranges making up the set, in order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<u64>> + '_ {
+        self.ranges.iter().map(|(lo, hi)| *lo..=*hi)
+    }
+}
+
+impl From<RangeInclusive<u64>> for IntervalSet {
+    fn from(range: RangeInclusive<u64>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(
+        iter: I,
+    ) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// Each range as `lo-hi`, or just `n` for one number, separated by commas.
+impl std::fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (lo, hi)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            match lo == hi {
+                true => write!(f, "{lo}")?,
+                false => write!(f, "{lo}-{hi}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How many of the given sets contain each number, as the ranges of numbers
+/// in at least one set, in order, each with its count, found by sweeping
+/// over where the sets' ranges start and end.
+pub fn sweep<'a>(
+    sets: impl IntoIterator<Item = &'a IntervalSet>,
+) -> Vec<(RangeInclusive<u64>, usize)> {
+    // Changes in the count, just past a range being at `u64::MAX + 1`.
+    let mut deltas: Vec<(u128, isize)> = Vec::new();
+    for set in sets {
+        for (lo, hi) in set.ranges.iter() {
+            deltas.push((*lo as u128, 1));
+            deltas.push((*hi as u128 + 1, -1));
+        }
+    }
+    deltas.sort();
+    let mut counts = Vec::new();
+    let mut count = 0;
+    for (i, (at, delta)) in deltas.iter().enumerate() {
+        count += delta;
+        match deltas.get(i + 1) {
+            Some((next, _)) if next > at && count > 0 => {
+                counts.push((*at as u64..=(next - 1) as u64, count as usize))
+            }
+            _ => {}
+        }
+    }
+    counts
+}
+
+fn is_2nd_range_contained_in_1st(
+    (r1_lo, r1_hi): &(u64, u64),
+    (r2_lo, r2_hi): &(u64, u64),
+) -> bool {
+    r2_lo >= r1_lo && r2_hi <= r1_hi
+}
+
+fn is_ranges_overlap(
+    (r1_lo, r1_hi): &(u64, u64),
+    (r2_lo, r2_hi): &(u64, u64),
+) -> bool {
+    r1_hi >= r2_lo && r1_lo <= r2_hi
+}
+
+#[test]
+fn range_containment() {
+    assert!(is_2nd_range_contained_in_1st(&(1, 5), &(2, 4)));
+    assert!(!is_2nd_range_contained_in_1st(&(1, 5), &(2, 6)));
+}
+
+#[test]
+fn range_overlap() {
+    assert!(is_ranges_overlap(&(1, 5), &(2, 4)));
+    assert!(is_ranges_overlap(&(1, 5), &(4, 8)));
+    assert!(!is_ranges_overlap(&(1, 5), &(6, 8)));
+}
+
+#[test]
+fn interval_insert() {
+    let mut set = IntervalSet::new();
+    set.insert(10..=12);
+    set.insert(1..=3);
+    set.insert(5..=5);
+    assert_eq!("1-3,5,10-12", set.to_string());
+    set.insert(4..=4);
+    assert_eq!("1-5,10-12", set.to_string());
+    set.insert(7..=20);
+    assert_eq!("1-5,7-20", set.to_string());
+    set.insert(0..=30);
+    assert_eq!("0-30", set.to_string());
+    let (lo, hi) = (3, 2);
+    set.insert(lo..=hi);
+    assert_eq!(31, set.len());
+    set.insert(u64::MAX - 1..=u64::MAX);
+    assert!(set.contains(u64::MAX) && !set.contains(31));
+}
+
+#[test]
+fn interval_algebra() {
+    let a: IntervalSet = [1..=5, 10..=15].into_iter().collect();
+    let b: IntervalSet = [4..=11, 20..=20].into_iter().collect();
+    assert_eq!("1-15,20", a.union(&b).to_string());
+    assert_eq!("4-5,10-11", a.intersection(&b).to_string());
+    assert_eq!("1-3,12-15", a.difference(&b).to_string());
+    assert_eq!("6-9,16-20", a.complement(2..=20).to_string());
+    assert_eq!("0,6-9,16-17", a.complement(0..=17).to_string());
+    assert_eq!(11, a.len());
+    assert!(a.overlaps(&b) && !a.overlaps(&IntervalSet::from(6..=9)));
+    assert!(a.contains_set(&IntervalSet::from(11..=14)));
+    assert!(!a.contains_set(&IntervalSet::from(5..=10)));
+    assert!(a.contains_set(&IntervalSet::new()));
+    assert_eq!(Some(1..=15), a.span());
+    assert_eq!("0-9", IntervalSet::new().complement(0..=9).to_string());
+}
+
+#[test]
+fn interval_sweep() {
+    let sets = [
+        IntervalSet::from(1..=5),
+        IntervalSet::from(3..=8),
+        [4..=4, 10..=10].into_iter().collect(),
+        IntervalSet::from(u64::MAX..=u64::MAX),
+    ];
+    let counts = sweep(&sets);
+    assert_eq!(
+        vec![
+            (1..=2, 1),
+            (3..=3, 2),
+            (4..=4, 3),
+            (5..=5, 2),
+            (6..=8, 1),
+            (10..=10, 1),
+            (u64::MAX..=u64::MAX, 1)
+        ],
+        counts
+    );
+    assert_eq!(Vec::<(RangeInclusive<u64>, usize)>::new(), sweep([]));
+}
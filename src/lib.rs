@@ -1,3 +1,9 @@
+pub mod geom;
+pub mod grid;
+pub mod interval;
+pub mod runner;
+pub mod search;
+
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
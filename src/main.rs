@@ -0,0 +1,123 @@
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use tracing_subscriber::{
+    filter::LevelFilter, fmt::format::FmtSpan, EnvFilter,
+};
+
+use aoc2022::runner::{self, Day, Expected, Input, Report, Status};
+
+const USAGE: &str = "usage: aoc2022 [--format text|json|csv] \
+    [--expected CSV_FILE] [example|input] [DAY ...]";
+
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+fn main() -> Result<()> {
+    // Silent unless asked otherwise via RUST_LOG, e.g. RUST_LOG=day_11=debug
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(LevelFilter::OFF.into())
+                .from_env_lossy(),
+        )
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .init();
+    let usage = |e: anyhow::Error| anyhow!("{e}\n{USAGE}");
+    let mut format = Format::Text;
+    let mut expected = Expected::default();
+    let mut input = Input::Input;
+    let mut selected: Vec<String> = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    f => return Err(usage(anyhow!("invalid format: {f:?}"))),
+                }
+            }
+            "--expected" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| usage(anyhow!("missing expected file")))?;
+                let file = std::fs::File::open(&path).map_err(|e| {
+                    anyhow!("Failure to open expected file {path:?}: {e:?}")
+                })?;
+                expected = Expected::read_csv(file)?;
+            }
+            _ if selected.is_empty()
+                && (arg == "example" || arg == "input") =>
+            {
+                input = arg.parse().map_err(usage)?;
+            }
+            _ if arg.starts_with("--") => {
+                return Err(usage(anyhow!("unknown option: {arg:?}")))
+            }
+            _ => selected.push(day_name(&arg)),
+        }
+    }
+    let days: Vec<&Day> = runner::select(&selected).map_err(usage)?;
+    let start = Instant::now();
+    let reports = runner::run_all(&days, input);
+    let wall = start.elapsed();
+    let stdout = std::io::stdout();
+    match format {
+        Format::Json => runner::write_json(stdout, &reports, &expected)?,
+        Format::Csv => runner::write_csv(stdout, &reports, &expected)?,
+        Format::Text => {
+            for report in reports.iter() {
+                print(report, report.status(&expected));
+            }
+            println!("total wall: {wall:.2?}");
+        }
+    }
+    Ok(())
+}
+
+/// Accept days as either "day_01", "01" or "1".
+fn day_name(arg: &str) -> String {
+    match arg.parse::<u8>() {
+        Ok(n) => format!("day_{n:02}"),
+        Err(_) => arg.to_string(),
+    }
+}
+
+fn print(
+    Report {
+        day,
+        part,
+        answer,
+        load,
+        solve,
+        ..
+    }: &Report,
+    status: Status,
+) {
+    let timing = format!(
+        "load: {:.2?} wall, {:.2?} cpu; solve: {:.2?} wall, {:.2?} cpu",
+        load.wall, load.cpu, solve.wall, solve.cpu
+    );
+    let mismatch = match status {
+        Status::Mismatch => "MISMATCH ",
+        _ => "",
+    };
+    match answer {
+        Ok(answer) if answer.contains('\n') => {
+            println!("{day} part {part}: {mismatch}({timing})");
+            for line in answer.lines() {
+                println!("    {line}");
+            }
+        }
+        Ok(answer) => {
+            println!("{day} part {part}: {mismatch}{answer} ({timing})")
+        }
+        Err(e) => println!("{day} part {part}: ERROR {e} ({timing})"),
+    }
+}
@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use cpu_time::ThreadTime;
+use rayon::prelude::*;
+
+/// Solution answers are of assorted types, but all get reported as text.
+pub trait Answer {
+    fn render(&self) -> String;
+}
+
+macro_rules! answer_via_display {
+    ($($t:ty),*) => {
+        $(
+            impl Answer for $t {
+                fn render(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+answer_via_display!(i32, u64, usize, String);
+
+impl<T: Answer> Answer for Option<T> {
+    fn render(&self) -> String {
+        match self {
+            None => "none".to_string(),
+            Some(answer) => answer.render(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Example,
+    Input,
+}
+
+impl Input {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Example => "example",
+            Self::Input => "input",
+        }
+    }
+
+    pub fn path(&self, day: &str) -> PathBuf {
+        Path::new("tests/input")
+            .join(day)
+            .join(format!("{}.txt", self.name()))
+    }
+}
+
+impl std::str::FromStr for Input {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "example" => Ok(Self::Example),
+            "input" => Ok(Self::Input),
+            _ => Err(anyhow!("invalid input kind: {s:?}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timing {
+    pub wall: Duration,
+    pub cpu: Duration,
+}
+
+impl Timing {
+    /// CPU time is that of the calling thread, so work which the `parallel`
+    /// feature hands off to other threads is only reflected in wall time.
+    fn measure<T>(f: impl FnOnce() -> T) -> (T, Self) {
+        let wall = Instant::now();
+        let cpu = ThreadTime::now();
+        let t = f();
+        let timing = Self {
+            wall: wall.elapsed(),
+            cpu: cpu.elapsed(),
+        };
+        (t, timing)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub day: &'static str,
+    pub part: u8,
+    pub input: PathBuf,
+    pub answer: std::result::Result<String, String>,
+    pub load: Timing,
+    pub solve: Timing,
+}
+
+pub struct Day {
+    pub name: &'static str,
+    run: fn(&'static str, &Path) -> [Report; 2],
+}
+
+impl Day {
+    pub fn run(&self, input: &Path) -> [Report; 2] {
+        (self.run)(self.name, input)
+    }
+}
+
+macro_rules! days {
+    ($($day:ident),* $(,)?) => {
+        pub const DAYS: &[Day] = &[
+            $(
+                Day {
+                    name: stringify!($day),
+                    run: |name, input| {
+                        run(
+                            name,
+                            input,
+                            crate::$day::Data::load,
+                            crate::$day::Data::solve1,
+                            crate::$day::Data::solve2,
+                        )
+                    },
+                },
+            )*
+        ];
+    };
+}
+
+days! {
+    day_01,
+    day_02,
+    day_03,
+    day_04,
+    day_05,
+    day_06,
+    day_07,
+    day_08,
+    day_09,
+    day_10,
+    day_11,
+    day_18,
+}
+
+/// Loads each day once, then solves both of its parts concurrently.
+fn run<D: Sync, A1: Answer, A2: Answer>(
+    day: &'static str,
+    input: &Path,
+    load: fn(&Path) -> Result<D>,
+    solve1: fn(&D) -> Result<A1>,
+    solve2: fn(&D) -> Result<A2>,
+) -> [Report; 2] {
+    let (data, load_timing) = Timing::measure(|| load(input));
+    let report = |part, (answer, solve): (Result<String>, Timing)| Report {
+        day,
+        part,
+        input: input.to_path_buf(),
+        answer: answer.map_err(|e| format!("{e:?}")),
+        load: load_timing,
+        solve,
+    };
+    match data {
+        Err(e) => {
+            let e = e.context("failure to load");
+            let answer = || (Err(anyhow!("{e:?}")), Timing::default());
+            [report(1, answer()), report(2, answer())]
+        }
+        Ok(data) => {
+            let (part1, part2) = rayon::join(
+                || Timing::measure(|| solve1(&data).map(|a| a.render())),
+                || Timing::measure(|| solve2(&data).map(|a| a.render())),
+            );
+            [report(1, part1), report(2, part2)]
+        }
+    }
+}
+
+/// The days with the given names, each once, in order of [`DAYS`], or all
+/// of them if no name is given. Names matching no day are an error.
+pub fn select(names: &[String]) -> Result<Vec<&'static Day>> {
+    let mut unknown: Vec<&str> = Vec::new();
+    for name in names {
+        if !DAYS.iter().any(|d| d.name == name)
+            && !unknown.contains(&name.as_str())
+        {
+            unknown.push(name);
+        }
+    }
+    if !unknown.is_empty() {
+        return Err(anyhow!("unknown day in {unknown:?}"));
+    }
+    Ok(DAYS
+        .iter()
+        .filter(|d| names.is_empty() || names.iter().any(|n| n == d.name))
+        .collect())
+}
+
+/// Runs the given days concurrently, returning reports in the given order.
+pub fn run_all(days: &[&Day], input: Input) -> Vec<Report> {
+    days.par_iter()
+        .map(|day| day.run(&input.path(day.name)))
+        .collect::<Vec<[Report; 2]>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Err,
+    Mismatch,
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Err => "err",
+            Self::Mismatch => "mismatch",
+        }
+    }
+}
+
+/// Known answers, keyed by day, part and input file.
+#[derive(Debug, Default)]
+pub struct Expected {
+    answers: HashMap<(String, u8, PathBuf), String>,
+}
+
+impl Expected {
+    /// Reads the "ok" answers from the output of a previous [`write_csv`].
+    pub fn read_csv<R: Read>(mut reader: R) -> Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut records = csv_records(&text)?.into_iter();
+        match records.next() {
+            Some(header) if header == CSV_HEADER => (),
+            header => return Err(anyhow!("invalid CSV header: {header:?}")),
+        }
+        let mut answers = HashMap::new();
+        for (rn, record) in records.enumerate().map(|(i, r)| (i + 2, r)) {
+            match &record[..] {
+                [day, part, input, answer, _, _, status] => {
+                    if status == Status::Ok.name() {
+                        let key = (day.clone(), part.parse()?, input.into());
+                        answers.insert(key, answer.clone());
+                    }
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "invalid CSV record {rn}: {record:?}"
+                    ))
+                }
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    fn get(&self, report: &Report) -> Option<&String> {
+        let key = (report.day.to_string(), report.part, report.input.clone());
+        self.answers.get(&key)
+    }
+}
+
+impl Report {
+    pub fn status(&self, expected: &Expected) -> Status {
+        match (&self.answer, expected.get(self)) {
+            (Err(_), _) => Status::Err,
+            (Ok(answer), Some(expected)) if answer != expected => {
+                Status::Mismatch
+            }
+            (Ok(_), _) => Status::Ok,
+        }
+    }
+
+    /// The answer, or the error message in its place.
+    fn answer_text(&self) -> &str {
+        match &self.answer {
+            Ok(answer) => answer,
+            Err(e) => e,
+        }
+    }
+}
+
+const CSV_HEADER: [&str; 7] = [
+    "day",
+    "part",
+    "input",
+    "answer",
+    "parse_secs",
+    "solve_secs",
+    "status",
+];
+
+/// Writes one JSON object per line, per report.
+pub fn write_json<W: Write>(
+    mut w: W,
+    reports: &[Report],
+    expected: &Expected,
+) -> Result<()> {
+    for r in reports {
+        writeln!(
+            w,
+            "{{\"day\":{},\"part\":{},\"input\":{},\"answer\":{},\
+            \"parse_secs\":{},\"solve_secs\":{},\"status\":{}}}",
+            json_string(r.day),
+            r.part,
+            json_string(&r.input.to_string_lossy()),
+            json_string(r.answer_text()),
+            r.load.wall.as_secs_f64(),
+            r.solve.wall.as_secs_f64(),
+            json_string(r.status(expected).name()),
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a header line followed by a record per report.
+pub fn write_csv<W: Write>(
+    mut w: W,
+    reports: &[Report],
+    expected: &Expected,
+) -> Result<()> {
+    writeln!(w, "{}", CSV_HEADER.join(","))?;
+    for r in reports {
+        let fields = [
+            csv_field(r.day),
+            r.part.to_string(),
+            csv_field(&r.input.to_string_lossy()),
+            csv_field(r.answer_text()),
+            r.load.wall.as_secs_f64().to_string(),
+            r.solve.wall.as_secs_f64().to_string(),
+            r.status(expected).name().to_string(),
+        ];
+        writeln!(w, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+fn json_string(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len() + 2);
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => {
+                buf.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+    buf
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Splits RFC 4180 text into records of unescaped fields.
+fn csv_records(s: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => (),
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(anyhow!("unterminated quoted CSV field: {field:?}"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[test]
+fn json_escaping() {
+    assert_eq!(r#""a\"b\\c""#, json_string(r#"a"b\c"#));
+    assert_eq!(r###""##.\n.##\n""###, json_string("##.\n.##\n"));
+    assert_eq!(r#""\u0007""#, json_string("\u{7}"));
+}
+
+#[test]
+fn csv_round_trip() {
+    let fields = ["plain", "a,b", "say \"hi\"", "##.\n.##\n", ""];
+    let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    let text = format!("{}\r\n{}\n", line.join(","), line.join(","));
+    let records = csv_records(&text).unwrap();
+    assert_eq!(vec![fields.to_vec(), fields.to_vec()], records);
+}
+
+#[test]
+fn day_selection() {
+    let names = |names: &[&str]| -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    };
+    let selected = |days: Vec<&Day>| -> Vec<&str> {
+        days.iter().map(|d| d.name).collect()
+    };
+    assert_eq!(DAYS.len(), select(&[]).unwrap().len());
+    let days = select(&names(&["day_02", "day_01", "day_02"])).unwrap();
+    assert_eq!(vec!["day_01", "day_02"], selected(days));
+    let err = select(&names(&["day_01", "day_99", "x", "day_99"]));
+    assert_eq!(
+        "unknown day in [\"day_99\", \"x\"]",
+        err.err().unwrap().to_string()
+    );
+}
@@ -0,0 +1,354 @@
+//! Graph searches over implicit graphs, given as a start state and a
+//! function from a state to its neighbours.
+//!
+//! Each search has a `_by_key` variant, which tracks visited states by the
+//! given key rather than by the whole state, so that states which differ only
+//! in irrelevant details (or which are expensive to hash) can be treated as
+//! one.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Every state reached by a search, each with the one it was reached from.
+struct Tree<S> {
+    nodes: Vec<(S, Option<usize>)>,
+}
+
+impl<S: Clone> Tree<S> {
+    fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    fn push(&mut self, state: S, parent: Option<usize>) -> usize {
+        self.nodes.push((state, parent));
+        self.nodes.len() - 1
+    }
+
+    fn state(&self, i: usize) -> &S {
+        &self.nodes[i].0
+    }
+
+    /// States from the start to the given one, inclusive.
+    fn path(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.nodes[i].0.clone()];
+        while let Some(parent) = self.nodes[i].1 {
+            path.push(self.nodes[parent].0.clone());
+            i = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Shortest path, in number of steps, from the start to a goal state.
+pub fn bfs<S, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    bfs_by_key(start, S::clone, neighbours, goal)
+}
+
+pub fn bfs_by_key<S, K, I>(
+    start: S,
+    mut key: impl FnMut(&S) -> K,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Vec<S>>
+where
+    S: Clone,
+    K: Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut tree = Tree::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    visited.insert(key(&start));
+    queue.push_back(tree.push(start, None));
+    while let Some(i) = queue.pop_front() {
+        if goal(tree.state(i)) {
+            return Some(tree.path(i));
+        }
+        let next: Vec<S> = neighbours(tree.state(i)).into_iter().collect();
+        for state in next {
+            if visited.insert(key(&state)) {
+                queue.push_back(tree.push(state, Some(i)));
+            }
+        }
+    }
+    None
+}
+
+/// Some path, not necessarily the shortest, from the start to a goal state.
+pub fn dfs<S, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Vec<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    dfs_by_key(start, S::clone, neighbours, goal)
+}
+
+pub fn dfs_by_key<S, K, I>(
+    start: S,
+    mut key: impl FnMut(&S) -> K,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Vec<S>>
+where
+    S: Clone,
+    K: Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut tree = Tree::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![tree.push(start, None)];
+    while let Some(i) = stack.pop() {
+        if !visited.insert(key(tree.state(i))) {
+            continue;
+        }
+        if goal(tree.state(i)) {
+            return Some(tree.path(i));
+        }
+        let next: Vec<S> = neighbours(tree.state(i)).into_iter().collect();
+        for state in next.into_iter().rev() {
+            if !visited.contains(&key(&state)) {
+                stack.push(tree.push(state, Some(i)));
+            }
+        }
+    }
+    None
+}
+
+/// Cheapest path from the start to a goal state and its total cost, given
+/// the neighbours of each state along with the cost of stepping to them.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_by_key(start, S::clone, neighbours, |_| C::default(), goal)
+}
+
+pub fn dijkstra_by_key<S, K, C, I>(
+    start: S,
+    key: impl FnMut(&S) -> K,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone,
+    K: Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_by_key(start, key, neighbours, |_| C::default(), goal)
+}
+
+/// Like [`dijkstra`], but guided by a heuristic estimate of the remaining
+/// cost to a goal, which must never overestimate it for the result to be
+/// the cheapest.
+pub fn astar<S, C, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar_by_key(start, S::clone, neighbours, heuristic, goal)
+}
+
+pub fn astar_by_key<S, K, C, I>(
+    start: S,
+    mut key: impl FnMut(&S) -> K,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<(Vec<S>, C)>
+where
+    S: Clone,
+    K: Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut tree = Tree::new();
+    let mut best: HashMap<K, C> = HashMap::new();
+    let mut frontier = BinaryHeap::new();
+    let zero = C::default();
+    best.insert(key(&start), zero);
+    let estimate = heuristic(&start);
+    frontier.push(Reverse((estimate, zero, tree.push(start, None))));
+    while let Some(Reverse((_, cost, i))) = frontier.pop() {
+        if best.get(&key(tree.state(i))).is_some_and(|c| *c < cost) {
+            continue; // Stale, got here cheaper since.
+        }
+        if goal(tree.state(i)) {
+            return Some((tree.path(i), cost));
+        }
+        let next: Vec<(S, C)> =
+            neighbours(tree.state(i)).into_iter().collect();
+        for (state, step) in next {
+            let cost = cost + step;
+            let k = key(&state);
+            if best.get(&k).is_none_or(|c| cost < *c) {
+                best.insert(k, cost);
+                let estimate = cost + heuristic(&state);
+                frontier.push(Reverse((
+                    estimate,
+                    cost,
+                    tree.push(state, Some(i)),
+                )));
+            }
+        }
+    }
+    None
+}
+
+/// All states reachable from the start, including itself.
+pub fn reachable<S, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+) -> HashSet<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    reachable_by_key(start, S::clone, neighbours)
+        .into_values()
+        .collect()
+}
+
+pub fn reachable_by_key<S, K, I>(
+    start: S,
+    mut key: impl FnMut(&S) -> K,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> HashMap<K, S>
+where
+    S: Clone,
+    K: Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = HashMap::new();
+    let mut stack = vec![start.clone()];
+    visited.insert(key(&start), start);
+    while let Some(state) = stack.pop() {
+        for next in neighbours(&state) {
+            if let Entry::Vacant(entry) = visited.entry(key(&next)) {
+                entry.insert(next.clone());
+                stack.push(next);
+            }
+        }
+    }
+    visited
+}
+
+/// Groups the given states into sets reachable from one another. The
+/// neighbours function is expected to only lead to other given states.
+pub fn connected_components<S, I>(
+    states: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> Vec<HashSet<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut components: Vec<HashSet<S>> = Vec::new();
+    for state in states {
+        if !components.iter().any(|c| c.contains(&state)) {
+            components.push(reachable(state, &mut neighbours));
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+fn grid_neighbours(
+    walls: &'static [&'static str],
+) -> impl FnMut(&(usize, usize)) -> Vec<(usize, usize)> {
+    move |&(r, k)| {
+        [
+            (r + 1, k),
+            (r, k + 1),
+            (r.wrapping_sub(1), k),
+            (r, k.wrapping_sub(1)),
+        ]
+        .into_iter()
+        .filter(|(r, k)| {
+            walls.get(*r).and_then(|row| row.as_bytes().get(*k))
+                == Some(&b'.')
+        })
+        .collect()
+    }
+}
+
+#[test]
+fn search_paths() {
+    let walls = &["..#", "#..", "..."];
+    let path = bfs((0, 0), grid_neighbours(walls), |p| *p == (2, 0)).unwrap();
+    assert_eq!(vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)], path);
+    let path = dfs((0, 0), grid_neighbours(walls), |p| *p == (2, 0)).unwrap();
+    assert_eq!(Some(&(2, 0)), path.last());
+    assert_eq!(None, bfs((0, 0), grid_neighbours(walls), |p| *p == (0, 2)));
+    assert_eq!(7, reachable((0, 0), grid_neighbours(walls)).len());
+}
+
+#[test]
+fn search_costs() {
+    // a -1-> b -1-> c, but a -5-> c and c -1-> d
+    let edges = |s: &char| match s {
+        'a' => vec![('b', 1), ('c', 5)],
+        'b' => vec![('c', 1)],
+        'c' => vec![('d', 1)],
+        _ => vec![],
+    };
+    let expected = Some((vec!['a', 'b', 'c', 'd'], 3));
+    assert_eq!(expected, dijkstra('a', edges, |s| *s == 'd'));
+    let h = |s: &char| ('d' as u32 - *s as u32).min(1);
+    assert_eq!(expected, astar('a', edges, h, |s| *s == 'd'));
+    assert_eq!(None, dijkstra('b', edges, |s| *s == 'a'));
+}
+
+#[test]
+fn search_by_key() {
+    // The second field is along for the ride and must not be hashed.
+    let steps = |&(n, _): &(u32, f64)| vec![(n + 1, 0.5), (n * 2, 0.25)];
+    let path = bfs_by_key((1, 0.0), |s| s.0, steps, |s| s.0 == 10).unwrap();
+    assert_eq!(
+        vec![1, 2, 4, 5, 10],
+        path.iter().map(|s| s.0).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn search_components() {
+    let edges = |n: &u32| match n {
+        1 => vec![2],
+        2 => vec![1, 3],
+        3 => vec![2],
+        4 => vec![5],
+        5 => vec![4],
+        _ => vec![],
+    };
+    let components = connected_components(1..=6, edges);
+    let sizes: Vec<usize> = components.iter().map(|c| c.len()).collect();
+    assert_eq!(vec![3, 2, 1], sizes);
+}
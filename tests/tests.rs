@@ -1,3 +1,17 @@
+/// Silent unless asked otherwise via RUST_LOG, e.g. RUST_LOG=debug
+fn init_tracing() {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::builder()
+                .with_default_directive(
+                    tracing_subscriber::filter::LevelFilter::OFF.into(),
+                )
+                .from_env_lossy(),
+        )
+        .with_test_writer()
+        .try_init();
+}
+
 macro_rules! path {
     ($day:expr, $type:expr) => {
         std::path::Path::new(concat!(
@@ -30,12 +44,14 @@ macro_rules! day {
                mod part_1 {
                    #[test]
                    fn example() {
+                       super::super::init_tracing();
                        let data = aoc2022::[<day $n>]::Data::load(path_to_example!($n)).unwrap();
                        assert_eq!($p1_ex, data.solve1().unwrap());
                    }
 
                    #[test]
                    fn input() {
+                       super::super::init_tracing();
                        let data = aoc2022::[<day $n>]::Data::load(path_to_input!($n)).unwrap();
                        assert_eq!($p1_in, data.solve1().unwrap());
                    }
@@ -43,12 +59,14 @@ macro_rules! day {
                mod part_2 {
                     #[test]
                     fn example() {
+                        super::super::init_tracing();
                         let data = aoc2022::[<day $n>]::Data::load(path_to_example!($n)).unwrap();
                         assert_eq!($p2_ex, data.solve2().unwrap());
                     }
 
                     #[test]
                     fn input() {
+                        super::super::init_tracing();
                         let data = aoc2022::[<day $n>]::Data::load(path_to_input!($n)).unwrap();
                         assert_eq!($p2_in, data.solve2().unwrap());
                     }